@@ -7,14 +7,26 @@ use tracing::warn;
 
 use inkwell::basic_block::BasicBlock;
 use inkwell::module::Module as InkwellModule;
-use inkwell::values::{FunctionValue, InstructionOpcode};
+use inkwell::values::{FunctionValue, InstructionOpcode, IntValue};
 
-use z3::ast::{Ast, Bool, Int};
+use inkwell::types::AnyType;
+
+use z3::ast::{Ast, Bool, Int, BV};
 use z3::Solver;
 
 use crate::codegen::codegen_instruction::codegen_instruction;
-use crate::symbolic_execution::{COMMON_END_NODE, PANIC_VAR_NAME};
-use crate::utils::var_utils::get_var_name;
+use crate::codegen::codegen_memory::{get_block_exit_memory_name, get_block_memory_name, get_memory_var, get_return_memory_name};
+use crate::codegen::codegen_summary::SummaryCache;
+use crate::error::WombatError;
+use crate::symbolic_execution::{
+    BOUNDS_CHECK_FUNCTION_NAME, BOUNDS_CHECK_PANIC_VAR_NAME, BOUND_EXHAUSTED_VAR_NAME, COMMON_END_NODE, DIVISION_BY_ZERO_PANIC_VAR_NAME, DIVISION_DIVIDEND_VAR_NAME, DIVISION_DIVISOR_VAR_NAME,
+    DIVISION_OVERFLOW_PANIC_VAR_NAME, PANIC_SITE_VAR_NAME, PANIC_VAR_NAME,
+};
+use crate::utils::function_utils::get_function_name;
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::panic_utils::{get_compared_operand, get_panic_site, PanicKind};
+use crate::utils::unroll_loops::{is_bound_exhausted_block, is_loop_cut_block};
+use crate::utils::var_utils::{get_called_function, get_checked_typed_var, get_operand_block, get_operand_value, get_var_name, IntegerEncoding};
 
 pub type EdgeSet = HashMap<String, HashSet<String>>;
 
@@ -22,7 +34,7 @@ fn get_basic_block_by_name<'a>(function: &'a FunctionValue, name: &String, names
     let mut matching_bb: Option<BasicBlock> = None;
     let mut matched = false;
     for bb in function.get_basic_blocks() {
-        let node_name = format!("{}{}", namespace, bb.get_name().to_str().unwrap());
+        let node_name = format!("{}{}", namespace, bb.get_name().to_string_lossy());
         if name.eq(&node_name) {
             if matched {
                 warn!("Multiple basic blocks matched name {:?}", name);
@@ -81,9 +93,58 @@ pub fn is_panic_block(bb: &BasicBlock) -> Option<bool> {
     };
 }
 
-pub fn get_entry_condition<'a>(solver: &'a Solver<'_>, function: &'a FunctionValue, predecessor: &str, node: &str, namespace: &str) -> Bool<'a> {
+pub fn is_bounds_check_block(module: &InkwellModule, bb: &BasicBlock) -> bool {
+    //! Check whether the block panics because an index is out of bounds
+    let mut next_instruction = bb.get_first_instruction();
+    while let Some(current_instruction) = next_instruction {
+        if current_instruction.get_opcode() == InstructionOpcode::Call {
+            if let Ok(call_operand) = get_called_function(module, &current_instruction) {
+                if get_function_name(&call_operand).starts_with(BOUNDS_CHECK_FUNCTION_NAME) {
+                    return true;
+                }
+            }
+        }
+        next_instruction = current_instruction.get_next_instruction();
+    }
+    false
+}
+
+fn get_division_operands_assignment<'a>(solver: &'a Solver<'_>, function: &FunctionValue, predecessor: &str, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
+    //! Record the operands of the failed division check, found among the equalities of the branch condition leading to the panic
+    let mut assignments = Vec::<Bool>::new();
+    let terminator = get_basic_block_by_name(function, &String::from(predecessor), namespace).and_then(|bb| bb.get_terminator());
+    if let Some(condition) = terminator
+        .filter(|terminator| terminator.get_opcode() == InstructionOpcode::Br && terminator.get_num_operands() == 3)
+        .and_then(|terminator| terminator.get_operand(0)?.left())
+    {
+        // Constants wider than 64 bits, such as the operands of an i128 division, are only available in their printed form
+        let get_constant_value = |constant: &IntValue| constant.print_to_string().to_str().ok()?.split_whitespace().last()?.parse::<i128>().ok();
+        let is_zero_or_minus_one = |constant: &IntValue| matches!(get_constant_value(constant), Some(0) | Some(-1));
+        let is_signed_min = |constant: &IntValue| {
+            let width = constant.get_type().get_bit_width();
+            (2..=128).contains(&width) && get_constant_value(constant) == Some(i128::MIN >> (128 - width))
+        };
+        for (var_name, operand) in [
+            (DIVISION_DIVISOR_VAR_NAME, get_compared_operand(&condition, &is_zero_or_minus_one)),
+            (DIVISION_DIVIDEND_VAR_NAME, get_compared_operand(&condition, &is_signed_min)),
+        ] {
+            if let Some(operand) = operand.filter(|operand| operand.is_int_value()) {
+                let operand_name = get_var_name(&operand, solver, namespace, encoding)?;
+                let operand_var = match encoding {
+                    IntegerEncoding::Int => Int::new_const(solver.get_context(), operand_name),
+                    IntegerEncoding::BitVector => BV::new_const(solver.get_context(), operand_name, operand.into_int_value().get_type().get_bit_width()).to_int(true),
+                };
+                assignments.push(Int::new_const(solver.get_context(), var_name)._eq(&operand_var));
+            }
+        }
+    }
+    Ok(Bool::and(solver.get_context(), &assignments.iter().collect::<Vec<&Bool>>()))
+}
+
+pub fn get_entry_condition<'a>(solver: &'a Solver<'_>, function: &'a FunctionValue, predecessor: &str, node: &str, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
     let mut entry_condition = Bool::from_bool(solver.get_context(), true);
-    if let Some(terminator) = get_basic_block_by_name(function, &String::from(predecessor), namespace).unwrap().get_terminator() {
+    let predecessor_basic_block = get_basic_block_by_name(function, &String::from(predecessor), namespace).ok_or_else(|| WombatError::BasicBlockNotFound(String::from(predecessor)))?;
+    if let Some(terminator) = predecessor_basic_block.get_terminator() {
         let opcode = terminator.get_opcode();
         let num_operands = terminator.get_num_operands();
         match &opcode {
@@ -92,14 +153,14 @@ pub fn get_entry_condition<'a>(solver: &'a Solver<'_>, function: &'a FunctionVal
                     // Unconditionally go to node
                 } else if num_operands == 3 {
                     let mut target_val = true;
-                    let discriminant = terminator.get_operand(0).unwrap().left().unwrap();
-                    let successor_basic_block_1 = terminator.get_operand(1).unwrap().right().unwrap();
-                    let successor_basic_block_name_1 = format!("{}{}", namespace, successor_basic_block_1.get_name().to_str().unwrap());
+                    let discriminant = get_operand_value(&terminator, 0)?;
+                    let successor_basic_block_1 = get_operand_block(&terminator, 1)?;
+                    let successor_basic_block_name_1 = format!("{}{}", namespace, successor_basic_block_1.get_name().to_string_lossy());
                     if successor_basic_block_name_1.eq(&String::from(node)) {
                         target_val = false;
                     }
                     let target_val_var = Bool::from_bool(solver.get_context(), target_val);
-                    let switch_var = Bool::new_const(solver.get_context(), get_var_name(&discriminant, solver, namespace));
+                    let switch_var = Bool::new_const(solver.get_context(), get_var_name(&discriminant, solver, namespace, encoding)?);
 
                     entry_condition = switch_var._eq(&target_val_var);
                 } else {
@@ -107,31 +168,32 @@ pub fn get_entry_condition<'a>(solver: &'a Solver<'_>, function: &'a FunctionVal
                 }
             }
             InstructionOpcode::Switch => {
-                let discriminant = terminator.get_operand(0).unwrap().left().unwrap();
-                let mut target_val = terminator.get_operand(0).unwrap().left().unwrap();
+                let discriminant = get_operand_value(&terminator, 0)?;
+                let mut target_val = discriminant;
                 for i in 0..num_operands {
                     if i % 2 == 1 {
-                        let basic_block = terminator.get_operand(i).unwrap().right().unwrap();
-                        let basic_block_name = format!("{}{}", namespace, basic_block.get_name().to_str().unwrap());
+                        let basic_block = get_operand_block(&terminator, i)?;
+                        let basic_block_name = format!("{}{}", namespace, basic_block.get_name().to_string_lossy());
                         if basic_block_name.eq(&String::from(node)) {
-                            target_val = terminator.get_operand(i - 1).unwrap().left().unwrap();
+                            target_val = get_operand_value(&terminator, i - 1)?;
                             break;
                         }
                     }
                 }
-                let switch_var = Int::new_const(solver.get_context(), get_var_name(&discriminant, solver, namespace));
+                let discriminant_type = discriminant.get_type().as_any_type_enum();
+                let switch_var = get_checked_typed_var(solver, &get_var_name(&discriminant, solver, namespace, encoding)?, &discriminant_type, encoding)?;
 
-                if target_val == terminator.get_operand(0).unwrap().left().unwrap() {
+                if target_val == discriminant {
                     // default
                     for j in 2..num_operands {
                         if j % 2 == 0 {
-                            let temp_target_val = terminator.get_operand(j).unwrap().left().unwrap();
-                            let temp_target_val_var = Int::new_const(solver.get_context(), get_var_name(&temp_target_val, solver, namespace));
+                            let temp_target_val = get_operand_value(&terminator, j)?;
+                            let temp_target_val_var = get_checked_typed_var(solver, &get_var_name(&temp_target_val, solver, namespace, encoding)?, &discriminant_type, encoding)?;
                             entry_condition = Bool::and(solver.get_context(), &[&(switch_var._eq(&temp_target_val_var)).not(), &entry_condition]);
                         }
                     }
                 } else {
-                    let target_val_var = Int::new_const(solver.get_context(), get_var_name(&target_val, solver, namespace));
+                    let target_val_var = get_checked_typed_var(solver, &get_var_name(&target_val, solver, namespace, encoding)?, &discriminant_type, encoding)?;
                     entry_condition = switch_var._eq(&target_val_var);
                 }
             }
@@ -148,21 +210,24 @@ pub fn get_entry_condition<'a>(solver: &'a Solver<'_>, function: &'a FunctionVal
     } else {
         warn!("\tNo terminator found when computing node entry conditions");
     }
-    entry_condition
+    Ok(entry_condition)
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn codegen_basic_block(
-    module: &InkwellModule,
+pub fn codegen_basic_block<'ctx>(
+    module: &AnalysedModule,
     node: String,
     forward_edges: &EdgeSet,
     backward_edges: &EdgeSet,
     function: &FunctionValue,
-    solver: &Solver,
+    solver: &Solver<'ctx>,
     namespace: &str,
     call_stack: &str,
     return_register: &str,
-) {
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: Option<&SummaryCache<'ctx>>,
+) -> Result<(), WombatError> {
     let mut successor_conditions = Bool::from_bool(solver.get_context(), true);
     if let Some(successors) = forward_edges.get(&node) {
         for successor in successors {
@@ -172,21 +237,87 @@ pub fn codegen_basic_block(
     }
     let mut node_var = successor_conditions;
 
-    if forward_edges.get(&node).is_some() && forward_edges.get(&node).unwrap().contains(COMMON_END_NODE) {
+    if forward_edges.get(&node).map_or(false, |successors| successors.contains(COMMON_END_NODE)) {
         // assign panic_var
         let lvalue_var = Bool::new_const(solver.get_context(), PANIC_VAR_NAME);
-        let is_panic = is_panic_block(&get_basic_block_by_name(function, &node, namespace).unwrap()).unwrap_or(true);
-        let rvalue_var = Bool::from_bool(solver.get_context(), is_panic);
+        let basic_block = get_basic_block_by_name(function, &node, namespace).ok_or_else(|| WombatError::BasicBlockNotFound(node.clone()))?;
+        let rvalue_var = if is_bound_exhausted_block(&basic_block) {
+            // Executions cut off by loop unrolling only count as failing when checking if the bound was exhausted
+            Bool::new_const(solver.get_context(), BOUND_EXHAUSTED_VAR_NAME)
+        } else if is_loop_cut_block(&basic_block) {
+            // Executions past an inductive loop invariant are covered by the arbitrary iteration
+            Bool::from_bool(solver.get_context(), false)
+        } else {
+            Bool::from_bool(solver.get_context(), is_panic_block(&basic_block).unwrap_or(true))
+        };
         let assignment = lvalue_var._eq(&rvalue_var);
         node_var = assignment.implies(&node_var);
+
+        // assign bounds_check_panic_var
+        let bounds_check_panic_var = Bool::new_const(solver.get_context(), BOUNDS_CHECK_PANIC_VAR_NAME);
+        let assignment = bounds_check_panic_var._eq(&Bool::from_bool(solver.get_context(), is_bounds_check_block(module, &basic_block)));
+        node_var = assignment.implies(&node_var);
+
+        // assign panic_site_var, which identifies the panicking block across inlined copies of its function
+        let panic_site_var = Int::new_const(solver.get_context(), PANIC_SITE_VAR_NAME);
+        let assignment = panic_site_var._eq(&Int::from_u64(solver.get_context(), module.get_panic_site_id(&basic_block)));
+        node_var = assignment.implies(&node_var);
+
+        // assign division panic vars
+        let panic_kind = get_panic_site(module, &basic_block).kind;
+        for (var_name, kind) in [
+            (DIVISION_BY_ZERO_PANIC_VAR_NAME, PanicKind::DivisionByZero),
+            (DIVISION_OVERFLOW_PANIC_VAR_NAME, PanicKind::DivisionOverflow),
+        ] {
+            let division_panic_var = Bool::new_const(solver.get_context(), var_name);
+            let assignment = division_panic_var._eq(&Bool::from_bool(solver.get_context(), panic_kind == kind));
+            node_var = assignment.implies(&node_var);
+        }
+        if matches!(panic_kind, PanicKind::DivisionByZero | PanicKind::DivisionOverflow) {
+            for predecessor in backward_edges.get(&node).into_iter().flatten() {
+                let assignment = get_division_operands_assignment(solver, function, predecessor, namespace, encoding)?;
+                node_var = assignment.implies(&node_var);
+            }
+        }
+    }
+
+    if module.uses_memory(function) {
+        // Pass the memory on to the successors, or back to the caller when returning
+        let basic_block = get_basic_block_by_name(function, &node, namespace).ok_or_else(|| WombatError::BasicBlockNotFound(node.clone()))?;
+        let is_return_block = basic_block.get_terminator().map_or(false, |terminator| terminator.get_opcode() == InstructionOpcode::Return);
+        let exit_memory_var = get_memory_var(solver, &get_block_exit_memory_name(module, &node, basic_block.get_last_instruction()));
+        for successor in forward_edges.get(&node).into_iter().flatten() {
+            let successor_memory_name = if is_return_block {
+                get_return_memory_name(return_register)
+            } else if successor.eq(COMMON_END_NODE) {
+                continue;
+            } else {
+                get_block_memory_name(successor, 0)
+            };
+            let assignment = get_memory_var(solver, &successor_memory_name)._eq(&exit_memory_var);
+            node_var = assignment.implies(&node_var);
+        }
     }
 
     // Parse statements in the basic block
-    let mut prev_instruction = get_basic_block_by_name(function, &node, namespace).unwrap().get_last_instruction();
+    let mut prev_instruction = get_basic_block_by_name(function, &node, namespace)
+        .ok_or_else(|| WombatError::BasicBlockNotFound(node.clone()))?
+        .get_last_instruction();
 
     while let Some(current_instruction) = prev_instruction {
         // Process current instruction
-        node_var = codegen_instruction(module, node_var, current_instruction, solver, namespace, call_stack, return_register);
+        node_var = codegen_instruction(
+            module,
+            node_var,
+            current_instruction,
+            solver,
+            namespace,
+            call_stack,
+            return_register,
+            encoding,
+            recursion_bound,
+            summaries,
+        )?;
         prev_instruction = current_instruction.get_previous_instruction();
     }
 
@@ -195,7 +326,7 @@ pub fn codegen_basic_block(
         if !predecessors.is_empty() {
             for predecessor in predecessors {
                 // get conditions
-                let entry_condition = get_entry_condition(solver, function, predecessor, &node, namespace);
+                let entry_condition = get_entry_condition(solver, function, predecessor, &node, namespace, encoding)?;
                 entry_conditions = Bool::and(solver.get_context(), &[&entry_conditions, &entry_condition]);
             }
         }
@@ -204,4 +335,5 @@ pub fn codegen_basic_block(
 
     let named_node_var = Bool::new_const(solver.get_context(), String::from(&node));
     solver.assert(&named_node_var._eq(&node_var));
+    Ok(())
 }
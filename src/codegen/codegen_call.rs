@@ -4,21 +4,190 @@
 use tracing::warn;
 
 use inkwell::module::Module as InkwellModule;
+use inkwell::types::{AnyType, AnyTypeEnum};
 use inkwell::values::InstructionValue;
 
-use z3::ast::{Ast, Bool, Int};
+use z3::ast::{Ast, Bool, Int, BV};
 use z3::Solver;
 
+use crate::codegen::codegen_float::codegen_float_intrinsic;
 use crate::codegen::codegen_function::codegen_function;
+use crate::codegen::codegen_memory::{codegen_memory_intrinsic, get_block_memory_name, get_memory_name, get_memory_var, get_next_memory_name, get_return_memory_name};
+use crate::codegen::codegen_summary::{instantiate_function_summary, SummaryCache};
 use crate::control_flow_graph::forward_topological_sort;
-use crate::symbolic_execution::get_module_name_from_file_name;
-use crate::utils::function_utils::{get_function_by_name, get_function_name};
-use crate::utils::var_utils::{get_min_max_signed_int, get_var_name};
+use crate::error::WombatError;
+use crate::symbolic_execution::{BOUNDS_CHECK_FUNCTION_NAME, BOUNDS_CHECK_INDEX_VAR_NAME, BOUNDS_CHECK_LENGTH_VAR_NAME, BOUND_EXHAUSTED_VAR_NAME};
+use crate::utils::function_utils::{get_function_by_name, get_function_name, is_recursive_function};
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::unroll_loops::{
+    is_invariant_function_name, ASSERT_INVARIANT_FUNCTION_NAME, ASSUME_INVARIANT_FUNCTION_NAME, CUT_INVARIANT_FUNCTION_NAME, HAVOC_MEMORY_FUNCTION_NAME, NONDET_FUNCTION_NAME,
+};
+use crate::utils::var_utils::{
+    get_assignment, get_block_name, get_called_function, get_int_from_i128, get_int_from_u128, get_int_width, get_min_max_signed_int, get_min_max_unsigned_int, get_operand_value, get_unsigned_int,
+    get_var_name, get_wrapped_signed_int, IntegerEncoding,
+};
+
+pub const DEFAULT_RECURSION_BOUND: u32 = 5;
+const ENUM_PANIC_FUNCTION_NAMES: [&str; 3] = ["core::option::unwrap_failed", "core::option::expect_failed", "core::result::unwrap_failed"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OverflowOperation {
+    Add,
+    Sub,
+    Mul,
+}
+
+fn codegen_overflow_intrinsic<'a>(
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver,
+    namespace: &str,
+    encoding: IntegerEncoding,
+    operation: OverflowOperation,
+    is_signed: bool,
+) -> Result<Bool<'a>, WombatError> {
+    //! Assign the result (field .0) and overflow flag (field .1) of a llvm.{s,u}{add,sub,mul}.with.overflow intrinsic
+    let operand1 = get_operand_value(&instruction, 0)?;
+    let operand1_name = get_var_name(&operand1, solver, namespace, encoding)?;
+    let operand2_name = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
+    let width = get_int_width(&operand1.get_type().as_any_type_enum());
+
+    let lvalue_var_name_1 = format!("{}.0", get_var_name(&instruction, solver, namespace, encoding)?);
+    let lvalue_var_name_2 = format!("{}.1", get_var_name(&instruction, solver, namespace, encoding)?);
+
+    let (assignment_1, rvalue_var_2) = match encoding {
+        IntegerEncoding::Int => {
+            // Translate the intrinsic integer size to the min/max representable numbers
+            let (min_int, max_int) = if is_signed {
+                let (min_int_val, max_int_val) = get_min_max_signed_int(width)?;
+                (get_int_from_i128(solver, min_int_val), get_int_from_i128(solver, max_int_val))
+            } else {
+                let (min_int_val, max_int_val) = get_min_max_unsigned_int(width)?;
+                (get_int_from_u128(solver, min_int_val), get_int_from_u128(solver, max_int_val))
+            };
+
+            let mut operand1_var = Int::new_const(solver.get_context(), operand1_name);
+            let mut operand2_var = Int::new_const(solver.get_context(), operand2_name);
+            if !is_signed {
+                operand1_var = get_unsigned_int(solver, &operand1_var, width);
+                operand2_var = get_unsigned_int(solver, &operand2_var, width);
+            }
+            let lvalue_var_1 = Int::new_const(solver.get_context(), lvalue_var_name_1);
+            let rvalue_var_1 = match operation {
+                OverflowOperation::Add => Int::add(solver.get_context(), &[&operand1_var, &operand2_var]),
+                OverflowOperation::Sub => Int::sub(solver.get_context(), &[&operand1_var, &operand2_var]),
+                OverflowOperation::Mul => Int::mul(solver.get_context(), &[&operand1_var, &operand2_var]),
+            };
+
+            let rvalue_var_2 = Bool::or(solver.get_context(), &[&rvalue_var_1.gt(&max_int), &rvalue_var_1.lt(&min_int)]);
+            if is_signed {
+                (lvalue_var_1._eq(&rvalue_var_1), rvalue_var_2)
+            } else {
+                // Store the unsigned result in its two's complement form like every other Int
+                (lvalue_var_1._eq(&get_wrapped_signed_int(solver, &rvalue_var_1, width)), rvalue_var_2)
+            }
+        }
+        IntegerEncoding::BitVector => {
+            // Wraparound is native to bitvectors, so only the overflow predicates need to be added
+            let operand1_var = BV::new_const(solver.get_context(), operand1_name, width);
+            let operand2_var = BV::new_const(solver.get_context(), operand2_name, width);
+            let lvalue_var_1 = BV::new_const(solver.get_context(), lvalue_var_name_1, width);
+            let (rvalue_var_1, no_overflow) = match operation {
+                OverflowOperation::Add => {
+                    let no_overflow = operand1_var.bvadd_no_overflow(&operand2_var, is_signed);
+                    let no_underflow = if is_signed {
+                        operand1_var.bvadd_no_underflow(&operand2_var)
+                    } else {
+                        Bool::from_bool(solver.get_context(), true)
+                    };
+                    (operand1_var.bvadd(&operand2_var), Bool::and(solver.get_context(), &[&no_overflow, &no_underflow]))
+                }
+                OverflowOperation::Sub => {
+                    let no_overflow = if is_signed {
+                        operand1_var.bvsub_no_overflow(&operand2_var)
+                    } else {
+                        Bool::from_bool(solver.get_context(), true)
+                    };
+                    let no_underflow = operand1_var.bvsub_no_underflow(&operand2_var, is_signed);
+                    (operand1_var.bvsub(&operand2_var), Bool::and(solver.get_context(), &[&no_overflow, &no_underflow]))
+                }
+                OverflowOperation::Mul => {
+                    let no_overflow = operand1_var.bvmul_no_overflow(&operand2_var, is_signed);
+                    let no_underflow = if is_signed {
+                        operand1_var.bvmul_no_underflow(&operand2_var)
+                    } else {
+                        Bool::from_bool(solver.get_context(), true)
+                    };
+                    (operand1_var.bvmul(&operand2_var), Bool::and(solver.get_context(), &[&no_overflow, &no_underflow]))
+                }
+            };
+            (lvalue_var_1._eq(&rvalue_var_1), no_overflow.not())
+        }
+    };
+
+    let assignment_2 = Bool::new_const(solver.get_context(), lvalue_var_name_2)._eq(&rvalue_var_2);
+    let assignment = Bool::and(solver.get_context(), &[&assignment_1, &assignment_2]);
+    Ok(assignment.implies(&node_var))
+}
+
+pub fn is_general_call(module: &InkwellModule, call_operation_name: &str) -> bool {
+    //! Calls to functions with a body in the analysed module (other than invariant markers) are inlined,
+    //! whichever crate they were monomorphized or compiled from
+    if is_invariant_function_name(call_operation_name) {
+        return false;
+    }
+    get_function_by_name(module, &String::from(call_operation_name)).map_or(false, |function| function.count_basic_blocks() > 0)
+}
+
+fn get_return_register_name(instruction: &InstructionValue, solver: &Solver, namespace: &str, encoding: IntegerEncoding) -> Result<String, WombatError> {
+    //! Void calls have no result to name the return register after, so use the position of the call instead
+    if let AnyTypeEnum::VoidType(_) = instruction.get_type() {
+        let mut instruction_index = 0;
+        let mut prev_instruction = instruction.get_previous_instruction();
+        while let Some(current_instruction) = prev_instruction {
+            instruction_index += 1;
+            prev_instruction = current_instruction.get_previous_instruction();
+        }
+        Ok(format!("{}{}.call.{}", namespace, get_block_name(&instruction)?, instruction_index))
+    } else {
+        get_var_name(instruction, solver, namespace, encoding)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn codegen_general_call<'a, 'ctx>(
+    module: &AnalysedModule,
+    mut node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'ctx>,
+    namespace: &str,
+    call_stack: &str,
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: Option<&SummaryCache<'ctx>>,
+) -> Result<Bool<'a>, WombatError> {
+    // Recursive calls deeper than the recursion bound are not inlined and cut the path as bound exhausted
+    let call_operand = get_called_function(module, &instruction)?;
+    let called_function_name = call_operand.get_name().to_string_lossy();
+    let recursion_depth = call_stack.split(',').filter(|caller| *caller == called_function_name).count();
+    if recursion_depth > recursion_bound as usize {
+        warn!("Recursive call to {} in call stack {:?} exceeds the recursion bound", called_function_name, call_stack);
+        return Ok(Bool::new_const(solver.get_context(), BOUND_EXHAUSTED_VAR_NAME).not());
+    }
+
+    let call_operation_name_string = get_function_name(&call_operand);
+    let function = get_function_by_name(module, &call_operation_name_string).ok_or_else(|| WombatError::FunctionNotFound(call_operation_name_string.clone()))?;
+    let uses_memory = module.uses_memory(&function);
 
-fn codegen_general_call<'a>(module: &InkwellModule, mut node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver, namespace: &str, call_stack: &str) -> Bool<'a> {
     // Create named POST_NODE from node_var
-    let new_return_register_string = get_var_name(&instruction, solver, namespace);
+    let new_return_register_string = get_return_register_name(&instruction, solver, namespace, encoding)?;
     let new_return_register_str = new_return_register_string.as_str();
+    if uses_memory {
+        // Continue with the memory the called function returns with
+        let next_memory_var = get_memory_var(solver, &get_next_memory_name(module, &instruction, namespace)?);
+        let return_memory_var = get_memory_var(solver, &get_return_memory_name(new_return_register_str));
+        node_var = next_memory_var._eq(&return_memory_var).implies(&node_var);
+    }
     let post_node_name_string = format!("{}_{}", namespace, new_return_register_str);
     let post_node_name_str = post_node_name_string.as_str();
     let post_node = Bool::new_const(solver.get_context(), post_node_name_str);
@@ -26,53 +195,67 @@ fn codegen_general_call<'a>(module: &InkwellModule, mut node_var: Bool<'a>, inst
     let new_namespace = format!("{}.", post_node);
 
     // Code gen function with return to POST_NODE and request to assign return value to new return register
-    let call_operand = instruction.get_operand(instruction.get_num_operands() - 1).unwrap().left().unwrap().into_pointer_value();
-    let call_operation_name_string = get_function_name(&call_operand);
-    let function = get_function_by_name(module, &call_operation_name_string).unwrap();
-    let new_return_register_string = get_var_name(&instruction, solver, namespace);
-    let new_return_register_str = new_return_register_string.as_str();
-    let new_call_stack_string = format!("{},{}", call_stack, function.get_name().to_str().unwrap());
-    codegen_function(
-        module,
-        &function,
-        solver,
-        new_namespace.as_str(),
-        new_call_stack_string.as_str(),
-        post_node_name_str,
-        new_return_register_str,
-    );
+    let new_call_stack_string = format!("{},{}", call_stack, function.get_name().to_string_lossy());
+    match summaries {
+        Some(summaries) if !is_recursive_function(module, &function) => {
+            instantiate_function_summary(
+                module,
+                &function,
+                solver,
+                new_namespace.as_str(),
+                post_node_name_str,
+                new_return_register_str,
+                encoding,
+                recursion_bound,
+                summaries,
+            )?;
+        }
+        _ => {
+            codegen_function(
+                module,
+                &function,
+                solver,
+                new_namespace.as_str(),
+                new_call_stack_string.as_str(),
+                post_node_name_str,
+                new_return_register_str,
+                encoding,
+                recursion_bound,
+                summaries,
+            )?;
+        }
+    }
 
     // CALL_NODE: Start node of function
     let called_function_forward_sorted_nodes = forward_topological_sort(&function, &new_namespace, post_node_name_str);
     if !called_function_forward_sorted_nodes.is_empty() {
         let call_node_name = called_function_forward_sorted_nodes.first().unwrap();
         node_var = Bool::new_const(solver.get_context(), call_node_name.as_str());
+        if uses_memory {
+            // The called function starts with the memory of the caller
+            let entry_memory_var = get_memory_var(solver, &get_block_memory_name(call_node_name, 0));
+            let memory_var = get_memory_var(solver, &get_memory_name(module, &instruction, namespace)?);
+            node_var = entry_memory_var._eq(&memory_var).implies(&node_var);
+        }
     } else {
         // NO-OP
         node_var = post_node;
     }
 
     // PRE_NODE with CALL_NODE as successor: Assign call arguments
-    // Supports signed int types and booleans
-    assert!(u32::try_from(function.get_params().len()).unwrap() == instruction.get_num_operands() - 1);
-    for i in 0..function.get_params().len() {
-        let params = function.get_params();
-        let input = params.get(i).unwrap();
-        if input.get_type().to_string().eq("\"i1\"") {
-            let lvalue = Bool::new_const(solver.get_context(), get_var_name(input, solver, &new_namespace));
-            let rvalue = Bool::new_const(
-                solver.get_context(),
-                get_var_name(&instruction.get_operand(u32::try_from(i).unwrap()).unwrap().left().unwrap(), solver, namespace),
-            );
-            let assignment = lvalue._eq(&rvalue);
-            node_var = assignment.implies(&node_var);
-        } else if input.get_type().is_int_type() {
-            let lvalue = Int::new_const(solver.get_context(), get_var_name(input, solver, &new_namespace));
-            let rvalue = Int::new_const(
-                solver.get_context(),
-                get_var_name(&instruction.get_operand(u32::try_from(i).unwrap()).unwrap().left().unwrap(), solver, namespace),
-            );
-            let assignment = lvalue._eq(&rvalue);
+    // Supports int types and booleans
+    if u32::try_from(function.get_params().len()).ok() != Some(instruction.get_num_operands() - 1) {
+        return Err(WombatError::ArgumentCountMismatch {
+            function_name: call_operation_name_string,
+            parameter_count: function.get_params().len(),
+            argument_count: instruction.get_num_operands() - 1,
+        });
+    }
+    for (i, input) in (0..).zip(function.get_params().iter()) {
+        let input_type = input.get_type().as_any_type_enum();
+        let lvalue_name = get_var_name(input, solver, &new_namespace, encoding)?;
+        let rvalue_name = get_var_name(&get_operand_value(&instruction, i)?, solver, namespace, encoding)?;
+        if let Some(assignment) = get_assignment(solver, &lvalue_name, &rvalue_name, &input_type, encoding) {
             node_var = assignment.implies(&node_var);
         } else {
             warn!("Currently unsupported type {:?} for input parameter to {}", input.get_type().to_string(), call_operation_name_string);
@@ -80,112 +263,116 @@ fn codegen_general_call<'a>(module: &InkwellModule, mut node_var: Bool<'a>, inst
     }
 
     // Return PRE_NODE
-    node_var
+    Ok(node_var)
 }
 
-pub fn codegen_call<'a>(module: &InkwellModule, mut node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver, namespace: &str, call_stack: &str) -> Bool<'a> {
-    let call_operand = instruction.get_operand(instruction.get_num_operands() - 1).unwrap().left().unwrap().into_pointer_value();
+#[allow(clippy::too_many_arguments)]
+pub fn codegen_call<'a, 'ctx>(
+    module: &AnalysedModule,
+    mut node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'ctx>,
+    namespace: &str,
+    call_stack: &str,
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: Option<&SummaryCache<'ctx>>,
+) -> Result<Bool<'a>, WombatError> {
+    let call_operand = get_called_function(module, &instruction)?;
     let call_operation_name_string = get_function_name(&call_operand);
     let call_operation_name_str = call_operation_name_string.as_str();
 
-    let module_name = get_module_name_from_file_name(&String::from(module.get_name().to_str().unwrap()));
-    if call_operation_name_str.contains(&module_name) {
-        return codegen_general_call(module, node_var, instruction, solver, namespace, call_stack);
+    // Invariant markers may be defined in the analysed module so they are recognised before inlining
+    if is_invariant_function_name(call_operation_name_str) || call_operation_name_str.eq(ASSERT_INVARIANT_FUNCTION_NAME) {
+        // Invariant that must hold whenever it is reached
+        let condition_var = Bool::new_const(solver.get_context(), get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?);
+        return Ok(Bool::and(solver.get_context(), &[&condition_var, &node_var]));
+    } else if call_operation_name_str.eq(ASSUME_INVARIANT_FUNCTION_NAME) {
+        // Invariant assumed to hold in an arbitrary loop iteration
+        let condition_var = Bool::new_const(solver.get_context(), get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?);
+        return Ok(condition_var.implies(&node_var));
+    } else if call_operation_name_str.eq(CUT_INVARIANT_FUNCTION_NAME) {
+        // Invariant that must be re-established by an arbitrary loop iteration, after which the path is cut
+        return Ok(Bool::new_const(solver.get_context(), get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?));
+    }
+
+    if is_general_call(module, call_operation_name_str) {
+        return codegen_general_call(module, node_var, instruction, solver, namespace, call_stack, encoding, recursion_bound, summaries);
     }
 
     match call_operation_name_str {
         s if s.starts_with("llvm.sadd.with.overflow.i") => {
-            // Translate the intrinsic integer size to an i64 representing the min/max representable numbers
-            let s_size = &s[25..];
-            let (min_int_val, max_int_val) = get_min_max_signed_int(s_size);
-
-            let operand1_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
-
-            let lvalue_var_name_1 = format!("{}.0", get_var_name(&instruction, solver, namespace));
-            let lvalue_var_1 = Int::new_const(solver.get_context(), lvalue_var_name_1);
-            let rvalue_var_1 = Int::add(
-                solver.get_context(),
-                &[&Int::new_const(solver.get_context(), operand1_name), &Int::new_const(solver.get_context(), operand2_name)],
-            );
-
-            let assignment_1 = lvalue_var_1._eq(&rvalue_var_1);
-
-            let lvalue_var_name_2 = format!("{}.1", get_var_name(&instruction, solver, namespace));
-            let min_int = Int::from_i64(solver.get_context(), min_int_val);
-            let max_int = Int::from_i64(solver.get_context(), max_int_val);
-            let rvalue_var_2 = Bool::or(solver.get_context(), &[&rvalue_var_1.gt(&max_int), &rvalue_var_1.lt(&min_int)]);
-
-            let assignment_2 = Bool::new_const(solver.get_context(), lvalue_var_name_2)._eq(&rvalue_var_2);
-            let assignment = Bool::and(solver.get_context(), &[&assignment_1, &assignment_2]);
-            node_var = assignment.implies(&node_var);
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Add, true)?;
         }
         s if s.starts_with("llvm.ssub.with.overflow.i") => {
-            // Translate the intrinsic integer size to an i64 representing the min/max representable numbers
-            let s_size = &s[25..];
-            let (min_int_val, max_int_val) = get_min_max_signed_int(s_size);
-
-            let operand1_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
-
-            let lvalue_var_name_1 = format!("{}.0", get_var_name(&instruction, solver, namespace));
-            let lvalue_var_1 = Int::new_const(solver.get_context(), lvalue_var_name_1);
-            let rvalue_var_1 = Int::sub(
-                solver.get_context(),
-                &[&Int::new_const(solver.get_context(), operand1_name), &Int::new_const(solver.get_context(), operand2_name)],
-            );
-
-            let assignment_1 = lvalue_var_1._eq(&rvalue_var_1);
-
-            let lvalue_var_name_2 = format!("{}.1", get_var_name(&instruction, solver, namespace));
-            let min_int = Int::from_i64(solver.get_context(), min_int_val);
-            let max_int = Int::from_i64(solver.get_context(), max_int_val);
-            let rvalue_var_2 = Bool::or(solver.get_context(), &[&rvalue_var_1.gt(&max_int), &rvalue_var_1.lt(&min_int)]);
-
-            let assignment_2 = Bool::new_const(solver.get_context(), lvalue_var_name_2)._eq(&rvalue_var_2);
-            let assignment = Bool::and(solver.get_context(), &[&assignment_1, &assignment_2]);
-            node_var = assignment.implies(&node_var);
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Sub, true)?;
         }
         s if s.starts_with("llvm.smul.with.overflow.i") => {
-            // Translate the intrinsic integer size to an i64 representing the min/max representable numbers
-            let s_size = &s[25..];
-            let (min_int_val, max_int_val) = get_min_max_signed_int(s_size);
-
-            let operand1_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
-
-            let lvalue_var_name_1 = format!("{}.0", get_var_name(&instruction, solver, namespace));
-            let lvalue_var_1 = Int::new_const(solver.get_context(), lvalue_var_name_1);
-            let rvalue_var_1 = Int::mul(
-                solver.get_context(),
-                &[&Int::new_const(solver.get_context(), operand1_name), &Int::new_const(solver.get_context(), operand2_name)],
-            );
-
-            let assignment_1 = lvalue_var_1._eq(&rvalue_var_1);
-
-            let lvalue_var_name_2 = format!("{}.1", get_var_name(&instruction, solver, namespace));
-            let min_int = Int::from_i64(solver.get_context(), min_int_val);
-            let max_int = Int::from_i64(solver.get_context(), max_int_val);
-            let rvalue_var_2 = Bool::or(solver.get_context(), &[&rvalue_var_1.gt(&max_int), &rvalue_var_1.lt(&min_int)]);
-
-            let assignment_2 = Bool::new_const(solver.get_context(), lvalue_var_name_2)._eq(&rvalue_var_2);
-            let assignment = Bool::and(solver.get_context(), &[&assignment_1, &assignment_2]);
-            node_var = assignment.implies(&node_var);
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Mul, true)?;
+        }
+        s if s.starts_with("llvm.uadd.with.overflow.i") => {
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Add, false)?;
+        }
+        s if s.starts_with("llvm.usub.with.overflow.i") => {
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Sub, false)?;
+        }
+        s if s.starts_with("llvm.umul.with.overflow.i") => {
+            node_var = codegen_overflow_intrinsic(node_var, instruction, solver, namespace, encoding, OverflowOperation::Mul, false)?;
         }
         "llvm.expect.i1" => {
-            let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-            let operand1_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+            let operand1_name = get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?;
+            let operand2_name = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
             let rvalue_var = Bool::new_const(solver.get_context(), operand1_name)._eq(&Bool::new_const(solver.get_context(), operand2_name));
             let assignment = Bool::new_const(solver.get_context(), lvalue_var_name)._eq(&rvalue_var);
             node_var = assignment.implies(&node_var);
         }
-        s if s.starts_with("core::panicking::panic") => {
-            // NO-OP
+        s if s.starts_with("llvm.fptosi.sat")
+            || s.starts_with("llvm.fptoui.sat")
+            || s.starts_with("llvm.fabs")
+            || s.starts_with("llvm.sqrt")
+            || s.starts_with("llvm.minnum")
+            || s.starts_with("llvm.maxnum") =>
+        {
+            node_var = codegen_float_intrinsic(node_var, instruction, solver, namespace, encoding, s)?;
+        }
+        s if s.starts_with("llvm.memcpy") || s.starts_with("llvm.memmove") || s.starts_with("llvm.memset") => {
+            node_var = codegen_memory_intrinsic(module, node_var, instruction, solver, namespace, encoding)?;
+        }
+        s if s.starts_with(BOUNDS_CHECK_FUNCTION_NAME) => {
+            // Record the offending index and length, which are only meaningful if this bounds check panics
+            let mut assignments = Vec::<Bool>::new();
+            for (i, var_name) in (0..).zip([BOUNDS_CHECK_INDEX_VAR_NAME, BOUNDS_CHECK_LENGTH_VAR_NAME].iter()) {
+                let operand = get_operand_value(&instruction, i)?;
+                let operand_name = get_var_name(&operand, solver, namespace, encoding)?;
+                let width = get_int_width(&operand.get_type().as_any_type_enum());
+                let operand_var = match encoding {
+                    IntegerEncoding::Int => get_unsigned_int(solver, &Int::new_const(solver.get_context(), operand_name), width),
+                    IntegerEncoding::BitVector => BV::new_const(solver.get_context(), operand_name, width).to_int(false),
+                };
+                assignments.push(Int::new_const(solver.get_context(), *var_name)._eq(&operand_var));
+            }
+            let assignment = Bool::and(solver.get_context(), &assignments.iter().collect::<Vec<&Bool>>());
+            node_var = assignment.implies(&node_var);
+        }
+        s if s.starts_with("core::panicking::") || s.starts_with("std::panicking::begin_panic") => {
+            // NO-OP: the block ends in an unreachable terminator and is classified by the panic function and its constant arguments
+        }
+        s if ENUM_PANIC_FUNCTION_NAMES.iter().any(|name| s.starts_with(name)) => {
+            // NO-OP: unwrapping a `None` or an `Err` panics at the unreachable terminator that follows
+        }
+        s if s.starts_with("llvm.dbg.") => {
+            // NO-OP: debug info only describes the source variables
+        }
+        s if s.starts_with(NONDET_FUNCTION_NAME) => {
+            // NO-OP: the return register is left unconstrained
+        }
+        HAVOC_MEMORY_FUNCTION_NAME => {
+            // NO-OP: the memory version written by the call is left unconstrained
         }
         _ => {
             warn!("Unsupported Call function {:?}", call_operation_name_str);
         }
     }
-    node_var
+    Ok(node_var)
 }
@@ -0,0 +1,283 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use tracing::warn;
+
+use inkwell::types::{AnyType, AnyTypeEnum};
+use inkwell::values::{InstructionOpcode, InstructionValue};
+use inkwell::FloatPredicate;
+
+use z3::ast::{Bool, Int};
+use z3::Solver;
+
+use crate::error::WombatError;
+use crate::utils::var_utils::{get_float_format, get_float_sort_string, get_int_width, get_operand_value, get_var_name, IntegerEncoding};
+
+// Suffixes of the Ints holding the IEEE-754 bits of floats stored to and loaded from memory
+const STORED_BITS_SUFFIX: &str = ".stored_bits";
+const LOADED_BITS_SUFFIX: &str = ".loaded_bits";
+
+fn get_smt_sort(var_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<String> {
+    if let Some(float_format) = get_float_format(var_type) {
+        Some(get_float_sort_string(float_format))
+    } else if var_type.is_int_type() && get_int_width(var_type) == 1 {
+        Some(String::from("Bool"))
+    } else if var_type.is_int_type() {
+        match encoding {
+            IntegerEncoding::Int => Some(String::from("Int")),
+            IntegerEncoding::BitVector => Some(format!("(_ BitVec {})", get_int_width(var_type))),
+        }
+    } else {
+        None
+    }
+}
+
+fn get_smt_var(var_name: &str) -> String {
+    format!("|{}|", var_name)
+}
+
+fn get_definition<'a>(solver: &'a Solver<'_>, lvalue_name: &str, lvalue_type: &AnyTypeEnum, operands: &[(String, AnyTypeEnum)], term: &str, encoding: IntegerEncoding) -> Option<Bool<'a>> {
+    //! Bool that holds iff the lvalue equals the SMT-LIB term, used for the floating-point operations the Z3 bindings do not expose
+    let definition_name = format!("{}.definition", lvalue_name);
+    let mut smtlib = format!("(declare-fun {} () {}) ", get_smt_var(lvalue_name), get_smt_sort(lvalue_type, encoding)?);
+    for (i, (operand_name, operand_type)) in operands.iter().enumerate() {
+        // Each constant may only be declared once per script
+        if operands[..i].iter().any(|(previous_name, _)| previous_name.eq(operand_name)) {
+            continue;
+        }
+        smtlib.push_str(&format!("(declare-fun {} () {}) ", get_smt_var(operand_name), get_smt_sort(operand_type, encoding)?));
+    }
+    smtlib.push_str(&format!(
+        "(declare-fun {} () Bool) (assert (= {} (= {} {})))",
+        get_smt_var(&definition_name),
+        get_smt_var(&definition_name),
+        get_smt_var(lvalue_name),
+        term
+    ));
+    solver.from_string(smtlib);
+    Some(Bool::new_const(solver.get_context(), definition_name))
+}
+
+fn get_power_of_two_string(exponent: u32) -> String {
+    if exponent < 128 {
+        (1_u128 << exponent).to_string()
+    } else {
+        String::from("340282366920938463463374607431768211456")
+    }
+}
+
+fn get_comparison_term(predicate: FloatPredicate, operand_1: &str, operand_2: &str) -> String {
+    //! Ordered predicates are false and unordered predicates are true when either operand is NaN
+    let is_unordered = format!("(or (fp.isNaN {}) (fp.isNaN {}))", operand_1, operand_2);
+    let ordered_term = |operation: &str| format!("({} {} {})", operation, operand_1, operand_2);
+    match predicate {
+        FloatPredicate::OEQ => ordered_term("fp.eq"),
+        FloatPredicate::OGT => ordered_term("fp.gt"),
+        FloatPredicate::OGE => ordered_term("fp.geq"),
+        FloatPredicate::OLT => ordered_term("fp.lt"),
+        FloatPredicate::OLE => ordered_term("fp.leq"),
+        FloatPredicate::ONE => format!("(or {} {})", ordered_term("fp.lt"), ordered_term("fp.gt")),
+        FloatPredicate::ORD => format!("(not {})", is_unordered),
+        FloatPredicate::UNO => is_unordered,
+        FloatPredicate::UEQ => format!("(or {} {})", is_unordered, ordered_term("fp.eq")),
+        FloatPredicate::UGT => format!("(or {} {})", is_unordered, ordered_term("fp.gt")),
+        FloatPredicate::UGE => format!("(or {} {})", is_unordered, ordered_term("fp.geq")),
+        FloatPredicate::ULT => format!("(or {} {})", is_unordered, ordered_term("fp.lt")),
+        FloatPredicate::ULE => format!("(or {} {})", is_unordered, ordered_term("fp.leq")),
+        FloatPredicate::UNE => format!("(not {})", ordered_term("fp.eq")),
+        FloatPredicate::PredicateTrue => String::from("true"),
+        FloatPredicate::PredicateFalse => String::from("false"),
+    }
+}
+
+fn get_remainder_term(operand_1: &str, operand_2: &str) -> String {
+    //! LLVM frem truncates the quotient (like C fmod) while the IEEE-754 remainder rounds it to nearest, so fix up the sign of the IEEE remainder
+    format!(
+        "(let ((r (fp.rem {} {}))) (ite (or (fp.isZero r) (= (fp.isNegative r) (fp.isNegative {}))) r (ite (fp.isNegative {}) (fp.sub RNE r (fp.abs {})) (fp.add RNE r (fp.abs {})))))",
+        operand_1, operand_2, operand_1, operand_1, operand_2, operand_2
+    )
+}
+
+fn get_int_to_float_term(operand: &str, operand_type: &AnyTypeEnum, lvalue_type: &AnyTypeEnum, is_signed: bool, encoding: IntegerEncoding) -> Option<String> {
+    let (exponent_width, significand_width) = get_float_format(lvalue_type)?;
+    let width = get_int_width(operand_type);
+    if width == 1 {
+        return None;
+    }
+    let term = match (encoding, is_signed) {
+        (IntegerEncoding::BitVector, true) => format!("((_ to_fp {} {}) RNE {})", exponent_width, significand_width, operand),
+        (IntegerEncoding::BitVector, false) => format!("((_ to_fp_unsigned {} {}) RNE {})", exponent_width, significand_width, operand),
+        (IntegerEncoding::Int, true) => format!("((_ to_fp {} {}) RNE (to_real {}))", exponent_width, significand_width, operand),
+        (IntegerEncoding::Int, false) => {
+            // Ints hold the two's complement value, so reinterpret it as unsigned first
+            let unsigned_operand = format!("(ite (< {} 0) (+ {} {}) {})", operand, operand, get_power_of_two_string(width), operand);
+            format!("((_ to_fp {} {}) RNE (to_real {}))", exponent_width, significand_width, unsigned_operand)
+        }
+    };
+    Some(term)
+}
+
+fn get_float_to_int_term(operand: &str, operand_type: &AnyTypeEnum, lvalue_type: &AnyTypeEnum, is_signed: bool, encoding: IntegerEncoding) -> Option<String> {
+    //! Rust casts saturate: NaN becomes 0 and out of range values become the nearest bound (LLVM leaves them undefined)
+    let (exponent_width, significand_width) = get_float_format(operand_type)?;
+    let width = get_int_width(lvalue_type);
+    if width == 1 {
+        return None;
+    }
+    let to_float = |real: String| format!("((_ to_fp {} {}) RNE {})", exponent_width, significand_width, real);
+    // The bounds are powers of two, so they are exact in every float format
+    let (low, high) = if is_signed {
+        (
+            to_float(format!("(- {}.0)", get_power_of_two_string(width - 1))),
+            to_float(format!("{}.0", get_power_of_two_string(width - 1))),
+        )
+    } else {
+        (to_float(String::from("0.0")), to_float(format!("{}.0", get_power_of_two_string(width))))
+    };
+    let max_unsigned = (u128::MAX >> (128 - width)).to_string();
+    let (zero, min, max, truncated) = match encoding {
+        IntegerEncoding::BitVector => {
+            let bv = |value: String| format!("(_ bv{} {})", value, width);
+            let truncated = format!("((_ {} {}) RTZ {})", if is_signed { "fp.to_sbv" } else { "fp.to_ubv" }, width, operand);
+            if is_signed {
+                let min_magnitude = get_power_of_two_string(width - 1);
+                let max_signed = (u128::MAX >> (129 - width)).to_string();
+                (bv(String::from("0")), bv(min_magnitude), bv(max_signed), truncated)
+            } else {
+                (bv(String::from("0")), bv(String::from("0")), bv(max_unsigned), truncated)
+            }
+        }
+        IntegerEncoding::Int => {
+            let truncated = format!("(to_int (fp.to_real (fp.roundToIntegral RTZ {})))", operand);
+            if is_signed {
+                let min_signed = format!("(- {})", get_power_of_two_string(width - 1));
+                let max_signed = (u128::MAX >> (129 - width)).to_string();
+                (String::from("0"), min_signed, max_signed, truncated)
+            } else {
+                // Ints hold the two's complement value of the unsigned result
+                let wrapped = format!(
+                    "(let ((t {})) (ite (>= t {}) (- t {}) t))",
+                    truncated,
+                    get_power_of_two_string(width - 1),
+                    get_power_of_two_string(width)
+                );
+                (String::from("0"), String::from("0"), String::from("(- 1)"), wrapped)
+            }
+        }
+    };
+    Some(format!(
+        "(ite (fp.isNaN {}) {} (ite (fp.lt {} {}) {} (ite (fp.geq {} {}) {} {})))",
+        operand, zero, operand, low, min, operand, high, max, truncated
+    ))
+}
+
+pub fn codegen_float_instruction<'a>(node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver<'_>, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
+    //! Encode floating-point arithmetic, comparisons and conversions with IEEE-754 semantics (rounding to nearest, ties to even)
+    let lvalue_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let lvalue_type = instruction.get_type();
+    let mut operands = Vec::<(String, AnyTypeEnum)>::new();
+    for i in 0..instruction.get_num_operands() {
+        let operand = get_operand_value(&instruction, i)?;
+        operands.push((get_var_name(&operand, solver, namespace, encoding)?, operand.get_type().as_any_type_enum()));
+    }
+    let terms: Vec<String> = operands.iter().map(|(operand_name, _)| get_smt_var(operand_name)).collect();
+
+    let opcode = instruction.get_opcode();
+    let term = match opcode {
+        InstructionOpcode::FAdd => Some(format!("(fp.add RNE {} {})", terms[0], terms[1])),
+        InstructionOpcode::FSub => Some(format!("(fp.sub RNE {} {})", terms[0], terms[1])),
+        InstructionOpcode::FMul => Some(format!("(fp.mul RNE {} {})", terms[0], terms[1])),
+        InstructionOpcode::FDiv => Some(format!("(fp.div RNE {} {})", terms[0], terms[1])),
+        InstructionOpcode::FRem => Some(get_remainder_term(&terms[0], &terms[1])),
+        InstructionOpcode::FNeg => Some(format!("(fp.neg {})", terms[0])),
+        InstructionOpcode::FCmp => instruction.get_fcmp_predicate().map(|predicate| get_comparison_term(predicate, &terms[0], &terms[1])),
+        InstructionOpcode::FPExt | InstructionOpcode::FPTrunc => {
+            get_float_format(&lvalue_type).map(|(exponent_width, significand_width)| format!("((_ to_fp {} {}) RNE {})", exponent_width, significand_width, terms[0]))
+        }
+        InstructionOpcode::SIToFP => get_int_to_float_term(&terms[0], &operands[0].1, &lvalue_type, true, encoding),
+        InstructionOpcode::UIToFP => get_int_to_float_term(&terms[0], &operands[0].1, &lvalue_type, false, encoding),
+        InstructionOpcode::FPToSI => get_float_to_int_term(&terms[0], &operands[0].1, &lvalue_type, true, encoding),
+        InstructionOpcode::FPToUI => get_float_to_int_term(&terms[0], &operands[0].1, &lvalue_type, false, encoding),
+        _ => None,
+    };
+
+    match term.and_then(|term| get_definition(solver, &lvalue_name, &lvalue_type, &operands, &term, encoding)) {
+        Some(assignment) => Ok(assignment.implies(&node_var)),
+        None => {
+            warn!("Currently unsupported type {:?} for {:?}", lvalue_type.to_string(), opcode);
+            Ok(node_var)
+        }
+    }
+}
+
+pub fn codegen_float_intrinsic<'a>(
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+    intrinsic_name: &str,
+) -> Result<Bool<'a>, WombatError> {
+    //! Encode the llvm.fptosi.sat, llvm.fptoui.sat, llvm.fabs, llvm.sqrt, llvm.minnum and llvm.maxnum intrinsics
+    let lvalue_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let lvalue_type = instruction.get_type();
+    let mut operands = Vec::<(String, AnyTypeEnum)>::new();
+    // The last operand is the called function
+    for i in 0..instruction.get_num_operands() - 1 {
+        let operand = get_operand_value(&instruction, i)?;
+        operands.push((get_var_name(&operand, solver, namespace, encoding)?, operand.get_type().as_any_type_enum()));
+    }
+    let terms: Vec<String> = operands.iter().map(|(operand_name, _)| get_smt_var(operand_name)).collect();
+
+    let term = match intrinsic_name {
+        s if s.starts_with("llvm.fptosi.sat") => get_float_to_int_term(&terms[0], &operands[0].1, &lvalue_type, true, encoding),
+        s if s.starts_with("llvm.fptoui.sat") => get_float_to_int_term(&terms[0], &operands[0].1, &lvalue_type, false, encoding),
+        s if s.starts_with("llvm.fabs") => Some(format!("(fp.abs {})", terms[0])),
+        s if s.starts_with("llvm.sqrt") => Some(format!("(fp.sqrt RNE {})", terms[0])),
+        // Unlike the IEEE-754 minimum and maximum, minnum and maxnum return the other operand when one is NaN
+        s if s.starts_with("llvm.minnum") => Some(format!("(ite (fp.isNaN {}) {} (fp.min {} {}))", terms[0], terms[1], terms[0], terms[1])),
+        s if s.starts_with("llvm.maxnum") => Some(format!("(ite (fp.isNaN {}) {} (fp.max {} {}))", terms[0], terms[1], terms[0], terms[1])),
+        _ => None,
+    };
+
+    match term.and_then(|term| get_definition(solver, &lvalue_name, &lvalue_type, &operands, &term, encoding)) {
+        Some(assignment) => Ok(assignment.implies(&node_var)),
+        None => {
+            warn!("Currently unsupported type {:?} for {}", lvalue_type.to_string(), intrinsic_name);
+            Ok(node_var)
+        }
+    }
+}
+
+pub fn get_stored_float_bits<'a>(solver: &'a Solver<'_>, value_name: &str, value_type: &AnyTypeEnum) -> Option<Int<'a>> {
+    //! Memory cells hold the IEEE-754 bits of a float as an unsigned Int
+    let float_format = get_float_format(value_type)?;
+    let bits_name = format!("{}{}", value_name, STORED_BITS_SUFFIX);
+    solver.from_string(format!(
+        "(declare-fun {} () Int) (declare-fun {} () {}) (assert (= {} (bv2nat (fp.to_ieee_bv {}))))",
+        get_smt_var(&bits_name),
+        get_smt_var(value_name),
+        get_float_sort_string(float_format),
+        get_smt_var(&bits_name),
+        get_smt_var(value_name)
+    ));
+    Some(Int::new_const(solver.get_context(), bits_name))
+}
+
+pub fn get_loaded_float_bits<'a>(solver: &'a Solver<'_>, lvalue_name: &str, lvalue_type: &AnyTypeEnum) -> Option<Int<'a>> {
+    //! The float loaded from memory is the one with the IEEE-754 bits held by the returned Int
+    let (exponent_width, significand_width) = get_float_format(lvalue_type)?;
+    let bits_name = format!("{}{}", lvalue_name, LOADED_BITS_SUFFIX);
+    solver.from_string(format!(
+        "(declare-fun {} () Int) (declare-fun {} () {}) (assert (= {} ((_ to_fp {} {}) ((_ int2bv {}) {}))))",
+        get_smt_var(&bits_name),
+        get_smt_var(lvalue_name),
+        get_float_sort_string((exponent_width, significand_width)),
+        get_smt_var(lvalue_name),
+        exponent_width,
+        significand_width,
+        exponent_width + significand_width,
+        get_smt_var(&bits_name)
+    ));
+    Some(Int::new_const(solver.get_context(), bits_name))
+}
@@ -3,30 +3,50 @@
 
 use tracing::warn;
 
-use inkwell::module::Module as InkwellModule;
+use inkwell::types::AnyType;
 use inkwell::values::{InstructionOpcode, InstructionValue};
 use inkwell::IntPredicate;
 
-use z3::ast::{Ast, Bool, Int};
+use z3::ast::{Ast, Bool, Int, BV};
 use z3::Solver;
 
 use crate::codegen::codegen_call::codegen_call;
-use crate::utils::var_utils::get_var_name;
+use crate::codegen::codegen_float::codegen_float_instruction;
+use crate::codegen::codegen_integer::codegen_integer_instruction;
+use crate::codegen::codegen_memory::{codegen_alloca, codegen_bit_cast, codegen_get_element_ptr, codegen_load, codegen_store};
+use crate::codegen::codegen_summary::SummaryCache;
+use crate::error::WombatError;
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::var_utils::{
+    get_aggregate_leaves, get_assignment, get_field_var_name, get_instruction_string, get_int_width, get_operand_value, get_typed_var, get_unsigned_int, get_var_name, IntegerEncoding,
+};
 
-fn get_field_to_extract(instruction: &InstructionValue) -> String {
-    let instruction_string = instruction.to_string();
-    String::from(&instruction_string[instruction_string.rfind(' ').unwrap() + 1..instruction_string.rfind('\"').unwrap()])
+fn get_field_path(instruction: &InstructionValue) -> Vec<u32> {
+    //! The field indices of ExtractValue and InsertValue are the trailing constants of the instruction (after any metadata is skipped)
+    let instruction_string = instruction.to_string().replace('"', "");
+    let mut path: Vec<u32> = instruction_string
+        .split(',')
+        .rev()
+        .skip_while(|token| token.contains('!'))
+        .map_while(|token| token.trim().parse::<u32>().ok())
+        .collect();
+    path.reverse();
+    path
 }
 
-pub fn codegen_instruction<'a>(
-    module: &InkwellModule,
+#[allow(clippy::too_many_arguments)]
+pub fn codegen_instruction<'a, 'ctx>(
+    module: &AnalysedModule,
     mut node_var: Bool<'a>,
     instruction: InstructionValue,
-    solver: &'a Solver,
+    solver: &'a Solver<'ctx>,
     namespace: &'a str,
     call_stack: &str,
     return_register: &str,
-) -> Bool<'a> {
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: Option<&SummaryCache<'ctx>>,
+) -> Result<Bool<'a>, WombatError> {
     let opcode = instruction.get_opcode();
     match &opcode {
         InstructionOpcode::Unreachable => {
@@ -34,23 +54,16 @@ pub fn codegen_instruction<'a>(
         }
         InstructionOpcode::Call => {
             // Code gen function with return to POST_NODE and request to assign return value to new return register
-            node_var = codegen_call(module, node_var, instruction, solver, namespace, call_stack);
+            node_var = codegen_call(module, node_var, instruction, solver, namespace, call_stack, encoding, recursion_bound, summaries)?;
         }
         InstructionOpcode::Return => {
             if instruction.get_num_operands() == 0 {
                 // NO-OP
             } else if instruction.get_num_operands() == 1 {
-                let operand = instruction.get_operand(0).unwrap().left().unwrap();
-                let rvalue_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-                if operand.get_type().to_string().eq("\"i1\"") {
-                    let lvalue_var = Bool::new_const(solver.get_context(), return_register);
-                    let rvalue_var = Bool::new_const(solver.get_context(), rvalue_name);
-                    let assignment = lvalue_var._eq(&rvalue_var);
-                    node_var = assignment.implies(&node_var);
-                } else if operand.get_type().is_int_type() {
-                    let lvalue_var = Int::new_const(solver.get_context(), return_register);
-                    let rvalue_var = Int::new_const(solver.get_context(), rvalue_name);
-                    let assignment = lvalue_var._eq(&rvalue_var);
+                let operand = get_operand_value(&instruction, 0)?;
+                let rvalue_name = get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?;
+                let operand_type = operand.get_type().as_any_type_enum();
+                if let Some(assignment) = get_assignment(solver, return_register, &rvalue_name, &operand_type, encoding) {
                     node_var = assignment.implies(&node_var);
                 } else {
                     warn!("Currently unsupported type {:?} for return {:?}", operand.get_type().to_string(), instruction);
@@ -63,134 +76,188 @@ pub fn codegen_instruction<'a>(
             // NO-OP
         }
         InstructionOpcode::Load => {
-            // TODO: Support non-int types here
-            let operand = instruction.get_operand(0).unwrap().left().unwrap();
-            if !instruction.get_type().is_int_type() {
-                warn!("Currently unsupported type {:?} for load operand", instruction.get_type().to_string())
-            }
-            let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-            let rvalue_var_name = get_var_name(&operand, solver, namespace);
-            let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
-            let rvalue_var = Int::new_const(solver.get_context(), rvalue_var_name);
-            let assignment = lvalue_var._eq(&rvalue_var);
-            node_var = assignment.implies(&node_var);
+            node_var = codegen_load(module, node_var, instruction, solver, namespace, encoding)?;
         }
         InstructionOpcode::Store => {
-            // TODO: Support non-int types here
-            let operand1 = instruction.get_operand(0).unwrap().left().unwrap();
-            if !operand1.get_type().is_int_type() {
-                warn!("Currently unsupported type {:?} for store operand", operand1.get_type().to_string())
-            }
-            let operand2 = instruction.get_operand(1).unwrap().left().unwrap().into_pointer_value();
-
-            let lvalue_var_name = get_var_name(&operand1, solver, namespace);
-            let rvalue_var_name = get_var_name(&operand2, solver, namespace);
-            let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
-            let rvalue_var = Int::new_const(solver.get_context(), rvalue_var_name);
-            let assignment = lvalue_var._eq(&rvalue_var);
-            node_var = assignment.implies(&node_var);
+            node_var = codegen_store(module, node_var, instruction, solver, namespace, encoding)?;
+        }
+        InstructionOpcode::GetElementPtr => {
+            node_var = codegen_get_element_ptr(module, node_var, instruction, solver, namespace, encoding)?;
+        }
+        InstructionOpcode::BitCast => {
+            node_var = codegen_bit_cast(node_var, instruction, solver, namespace, encoding)?;
         }
         InstructionOpcode::Br => {
             // NO-OP
         }
-        InstructionOpcode::Xor => {
-            let operand1_var_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2_var_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
-            if !instruction.get_type().to_string().eq("\"i1\"") {
-                warn!("Currently unsupported type {:?} for xor operand", instruction.get_type().to_string());
-            }
-            let operand1_var = Bool::new_const(solver.get_context(), operand1_var_name);
-            let operand2_var = Bool::new_const(solver.get_context(), operand2_var_name);
-            let rvalue_var = operand1_var.xor(&operand2_var);
-            let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-            let lvalue_var = Bool::new_const(solver.get_context(), lvalue_var_name);
-            let assignment = lvalue_var._eq(&rvalue_var);
-            node_var = assignment.implies(&node_var);
+        InstructionOpcode::Add
+        | InstructionOpcode::Sub
+        | InstructionOpcode::Mul
+        | InstructionOpcode::SDiv
+        | InstructionOpcode::UDiv
+        | InstructionOpcode::SRem
+        | InstructionOpcode::URem
+        | InstructionOpcode::And
+        | InstructionOpcode::Or
+        | InstructionOpcode::Xor
+        | InstructionOpcode::Shl
+        | InstructionOpcode::LShr
+        | InstructionOpcode::AShr
+        | InstructionOpcode::SExt => {
+            node_var = codegen_integer_instruction(node_var, instruction, solver, namespace, encoding)?;
         }
         InstructionOpcode::ICmp => {
-            let lvalue_var_name = get_var_name(&instruction, solver, namespace);
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
             let lvalue_var = Bool::new_const(solver.get_context(), lvalue_var_name);
-            let operand1 = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-            let operand2 = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
+            let operand_type = get_operand_value(&instruction, 0)?.get_type().as_any_type_enum();
+            let operand1 = get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?;
+            let operand2 = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
 
             // Split by the sub-instruction (denoting the type of comparison)
-            // TODO: can signed & unsigned comparisons be combined?
-            let icmp_type = instruction.get_icmp_predicate().unwrap();
-            let rvalue_operation = match &icmp_type {
-                IntPredicate::EQ => Int::new_const(solver.get_context(), operand1)._eq(&Int::new_const(solver.get_context(), operand2)),
-                IntPredicate::NE => Int::new_const(solver.get_context(), operand1)._eq(&Int::new_const(solver.get_context(), operand2)).not(),
-                IntPredicate::SGE | IntPredicate::UGE => Int::new_const(solver.get_context(), operand1).ge(&Int::new_const(solver.get_context(), operand2)),
-                IntPredicate::SGT | IntPredicate::UGT => Int::new_const(solver.get_context(), operand1).gt(&Int::new_const(solver.get_context(), operand2)),
-                IntPredicate::SLE | IntPredicate::ULE => Int::new_const(solver.get_context(), operand1).le(&Int::new_const(solver.get_context(), operand2)),
-                IntPredicate::SLT | IntPredicate::ULT => Int::new_const(solver.get_context(), operand1).lt(&Int::new_const(solver.get_context(), operand2)),
+            let icmp_type = instruction
+                .get_icmp_predicate()
+                .ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(&instruction)))?;
+            let rvalue_operation = if encoding == IntegerEncoding::BitVector && operand_type.is_int_type() && get_int_width(&operand_type) > 1 {
+                // Bitvectors distinguish signed and unsigned comparisons
+                let width = get_int_width(&operand_type);
+                let operand1_var = BV::new_const(solver.get_context(), operand1, width);
+                let operand2_var = BV::new_const(solver.get_context(), operand2, width);
+                match &icmp_type {
+                    IntPredicate::EQ => operand1_var._eq(&operand2_var),
+                    IntPredicate::NE => operand1_var._eq(&operand2_var).not(),
+                    IntPredicate::SGE => operand1_var.bvsge(&operand2_var),
+                    IntPredicate::SGT => operand1_var.bvsgt(&operand2_var),
+                    IntPredicate::SLE => operand1_var.bvsle(&operand2_var),
+                    IntPredicate::SLT => operand1_var.bvslt(&operand2_var),
+                    IntPredicate::UGE => operand1_var.bvuge(&operand2_var),
+                    IntPredicate::UGT => operand1_var.bvugt(&operand2_var),
+                    IntPredicate::ULE => operand1_var.bvule(&operand2_var),
+                    IntPredicate::ULT => operand1_var.bvult(&operand2_var),
+                }
+            } else {
+                let operand1_var = Int::new_const(solver.get_context(), operand1);
+                let operand2_var = Int::new_const(solver.get_context(), operand2);
+                let (unsigned_operand1_var, unsigned_operand2_var) = if operand_type.is_int_type() && get_int_width(&operand_type) > 1 {
+                    // Ints hold the two's complement value, so reinterpret them before comparing as unsigned
+                    let width = get_int_width(&operand_type);
+                    (get_unsigned_int(solver, &operand1_var, width), get_unsigned_int(solver, &operand2_var, width))
+                } else {
+                    (operand1_var.clone(), operand2_var.clone())
+                };
+                match &icmp_type {
+                    IntPredicate::EQ => operand1_var._eq(&operand2_var),
+                    IntPredicate::NE => operand1_var._eq(&operand2_var).not(),
+                    IntPredicate::SGE => operand1_var.ge(&operand2_var),
+                    IntPredicate::SGT => operand1_var.gt(&operand2_var),
+                    IntPredicate::SLE => operand1_var.le(&operand2_var),
+                    IntPredicate::SLT => operand1_var.lt(&operand2_var),
+                    IntPredicate::UGE => unsigned_operand1_var.ge(&unsigned_operand2_var),
+                    IntPredicate::UGT => unsigned_operand1_var.gt(&unsigned_operand2_var),
+                    IntPredicate::ULE => unsigned_operand1_var.le(&unsigned_operand2_var),
+                    IntPredicate::ULT => unsigned_operand1_var.lt(&unsigned_operand2_var),
+                }
             };
 
             let assignment = lvalue_var._eq(&rvalue_operation);
             node_var = assignment.implies(&node_var);
         }
         InstructionOpcode::ExtractValue => {
-            let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-            let operand = instruction.get_operand(0).unwrap().left().unwrap();
-            let rvalue_var_name = format!("{}.{}", get_var_name(&operand, solver, namespace), get_field_to_extract(&instruction));
-            if instruction.get_type().to_string().eq("\"i1\"") {
-                let lvalue_var = Bool::new_const(solver.get_context(), lvalue_var_name);
-                let rvalue_var = Bool::new_const(solver.get_context(), rvalue_var_name);
-                let assignment = lvalue_var._eq(&rvalue_var);
-                node_var = assignment.implies(&node_var);
-            } else if instruction.get_type().is_int_type() {
-                let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
-                let rvalue_var = Int::new_const(solver.get_context(), rvalue_var_name);
-                let assignment = lvalue_var._eq(&rvalue_var);
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+            let operand = get_operand_value(&instruction, 0)?;
+            let rvalue_var_name = get_field_var_name(&get_var_name(&operand, solver, namespace, encoding)?, &get_field_path(&instruction));
+            if let Some(assignment) = get_assignment(solver, &lvalue_var_name, &rvalue_var_name, &instruction.get_type(), encoding) {
                 node_var = assignment.implies(&node_var);
             } else {
                 warn!("Currently unsupported type {:?} for extract value", operand.get_type().to_string())
             }
         }
+        InstructionOpcode::InsertValue => {
+            // The inserted fields are taken from the value and every other field from the aggregate operand
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+            let aggregate_var_name = get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?;
+            let value_var_name = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
+            let insert_path = get_field_path(&instruction);
+            let mut equalities = Vec::<Bool>::new();
+            for (path, leaf_type) in get_aggregate_leaves(&instruction.get_type()) {
+                let rvalue_var_name = if path.starts_with(&insert_path) {
+                    get_field_var_name(&value_var_name, &path[insert_path.len()..])
+                } else {
+                    get_field_var_name(&aggregate_var_name, &path)
+                };
+                if let (Some(lvalue_var), Some(rvalue_var)) = (
+                    get_typed_var(solver, &get_field_var_name(&lvalue_var_name, &path), &leaf_type, encoding),
+                    get_typed_var(solver, &rvalue_var_name, &leaf_type, encoding),
+                ) {
+                    equalities.push(lvalue_var._eq(&rvalue_var));
+                } else {
+                    warn!("Currently unsupported type {:?} for insert value", leaf_type.to_string());
+                }
+            }
+            let equality_refs: Vec<&Bool> = equalities.iter().collect();
+            let assignment = Bool::and(solver.get_context(), &equality_refs);
+            node_var = assignment.implies(&node_var);
+        }
+        InstructionOpcode::FAdd
+        | InstructionOpcode::FSub
+        | InstructionOpcode::FMul
+        | InstructionOpcode::FDiv
+        | InstructionOpcode::FRem
+        | InstructionOpcode::FNeg
+        | InstructionOpcode::FCmp
+        | InstructionOpcode::FPExt
+        | InstructionOpcode::FPTrunc
+        | InstructionOpcode::FPToSI
+        | InstructionOpcode::FPToUI
+        | InstructionOpcode::SIToFP
+        | InstructionOpcode::UIToFP => {
+            node_var = codegen_float_instruction(node_var, instruction, solver, namespace, encoding)?;
+        }
         InstructionOpcode::Alloca => {
-            // NO-OP
+            node_var = codegen_alloca(module, node_var, instruction, solver, namespace, encoding)?;
         }
         InstructionOpcode::Phi => {
             warn!("Phi instruction should be resolved and not exist during instruction codegen")
         }
         InstructionOpcode::Trunc => {
             if instruction.get_type().to_string().eq("\"i1\"") {
-                let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-                let operand_var_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
+                let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+                let operand = get_operand_value(&instruction, 0)?;
+                let operand_var_name = get_var_name(&operand, solver, namespace, encoding)?;
                 let lvalue_var = Bool::new_const(solver.get_context(), lvalue_var_name);
-                let operand_var = Int::new_const(solver.get_context(), operand_var_name);
-                let const_1 = Int::from_i64(solver.get_context(), 1);
-                let const_2 = Int::from_i64(solver.get_context(), 2);
-                let right_most_bit = operand_var.modulo(&const_2);
-                let assignment = lvalue_var._eq(&right_most_bit._eq(&const_1));
+                let right_most_bit_is_set = match encoding {
+                    IntegerEncoding::Int => {
+                        let operand_var = Int::new_const(solver.get_context(), operand_var_name);
+                        let const_1 = Int::from_i64(solver.get_context(), 1);
+                        let const_2 = Int::from_i64(solver.get_context(), 2);
+                        operand_var.modulo(&const_2)._eq(&const_1)
+                    }
+                    IntegerEncoding::BitVector => {
+                        let operand_var = BV::new_const(solver.get_context(), operand_var_name, get_int_width(&operand.get_type().as_any_type_enum()));
+                        operand_var.extract(0, 0)._eq(&BV::from_u64(solver.get_context(), 1, 1))
+                    }
+                };
+                let assignment = lvalue_var._eq(&right_most_bit_is_set);
                 node_var = assignment.implies(&node_var);
             } else {
-                warn!("Type {} is not a supported target type for the Trunc instruction!", instruction.get_type().to_string());
+                node_var = codegen_integer_instruction(node_var, instruction, solver, namespace, encoding)?;
             }
         }
         InstructionOpcode::Select => {
-            let discriminant = instruction.get_operand(0).unwrap().left().unwrap();
-            let discriminant_name = get_var_name(&discriminant, solver, namespace);
-            let operand_1_var_name = get_var_name(&instruction.get_operand(1).unwrap().left().unwrap(), solver, namespace);
-            let operand_2_var_name = get_var_name(&instruction.get_operand(2).unwrap().left().unwrap(), solver, namespace);
+            let discriminant = get_operand_value(&instruction, 0)?;
+            let discriminant_name = get_var_name(&discriminant, solver, namespace, encoding)?;
+            let operand_1_var_name = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
+            let operand_2_var_name = get_var_name(&get_operand_value(&instruction, 2)?, solver, namespace, encoding)?;
             if !discriminant.get_type().to_string().eq("\"i1\"") {
                 warn!("Currently unsupported type {:?} for select discriminant", discriminant.get_type().to_string());
             }
             let discriminant_var = Bool::new_const(solver.get_context(), discriminant_name);
-            if instruction.get_type().to_string().eq("\"i1\"") {
-                let operand_1_var = Bool::new_const(solver.get_context(), operand_1_var_name);
-                let operand_2_var = Bool::new_const(solver.get_context(), operand_2_var_name);
-                let select_1 = discriminant_var.implies(&Bool::new_const(solver.get_context(), get_var_name(&instruction, solver, namespace))._eq(&operand_1_var));
-                let select_2 = discriminant_var
-                    .not()
-                    .implies(&Bool::new_const(solver.get_context(), get_var_name(&instruction, solver, namespace))._eq(&operand_2_var));
-                node_var = Bool::and(solver.get_context(), &[&select_1.implies(&node_var), &select_2.implies(&node_var)]);
-            } else if instruction.get_type().is_int_type() {
-                let operand_1_var = Int::new_const(solver.get_context(), operand_1_var_name);
-                let operand_2_var = Int::new_const(solver.get_context(), operand_2_var_name);
-                let select_1 = discriminant_var.implies(&Int::new_const(solver.get_context(), get_var_name(&instruction, solver, namespace))._eq(&operand_1_var));
-                let select_2 = discriminant_var
-                    .not()
-                    .implies(&Int::new_const(solver.get_context(), get_var_name(&instruction, solver, namespace))._eq(&operand_2_var));
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+            if let (Some(assignment_1), Some(assignment_2)) = (
+                get_assignment(solver, &lvalue_var_name, &operand_1_var_name, &instruction.get_type(), encoding),
+                get_assignment(solver, &lvalue_var_name, &operand_2_var_name, &instruction.get_type(), encoding),
+            ) {
+                let select_1 = discriminant_var.implies(&assignment_1);
+                let select_2 = discriminant_var.not().implies(&assignment_2);
                 let assignment = Bool::and(solver.get_context(), &[&select_1, &select_2]);
                 node_var = assignment.implies(&node_var);
             } else {
@@ -198,23 +265,43 @@ pub fn codegen_instruction<'a>(
             }
         }
         InstructionOpcode::ZExt => {
-            if instruction.get_operand(0).unwrap().left().unwrap().get_type().to_string().eq("\"i1\"") {
-                let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-                let operand_var_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-                let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
+            let operand = get_operand_value(&instruction, 0)?;
+            let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+            let operand_var_name = get_var_name(&operand, solver, namespace, encoding)?;
+            if operand.get_type().to_string().eq("\"i1\"") {
                 let operand_var = Bool::new_const(solver.get_context(), operand_var_name);
-                let const_1 = Int::from_i64(solver.get_context(), 1);
-                let const_0 = Int::from_i64(solver.get_context(), 0);
-                let cast_1 = operand_var.implies(&lvalue_var._eq(&const_1));
-                let cast_2 = operand_var.not().implies(&lvalue_var._eq(&const_0));
-                let assignment = Bool::and(solver.get_context(), &[&cast_1, &cast_2]);
+                let assignment = match encoding {
+                    IntegerEncoding::Int => {
+                        let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
+                        let const_1 = Int::from_i64(solver.get_context(), 1);
+                        let const_0 = Int::from_i64(solver.get_context(), 0);
+                        lvalue_var._eq(&operand_var.ite(&const_1, &const_0))
+                    }
+                    IntegerEncoding::BitVector => {
+                        let width = get_int_width(&instruction.get_type());
+                        let lvalue_var = BV::new_const(solver.get_context(), lvalue_var_name, width);
+                        let const_1 = BV::from_u64(solver.get_context(), 1, width);
+                        let const_0 = BV::from_u64(solver.get_context(), 0, width);
+                        lvalue_var._eq(&operand_var.ite(&const_1, &const_0))
+                    }
+                };
                 node_var = assignment.implies(&node_var);
-            } else if instruction.get_operand(0).unwrap().left().unwrap().get_type().is_int_type() {
-                let lvalue_var_name = get_var_name(&instruction, solver, namespace);
-                let operand_var_name = get_var_name(&instruction.get_operand(0).unwrap().left().unwrap(), solver, namespace);
-                let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
-                let operand_var = Int::new_const(solver.get_context(), operand_var_name);
-                let assignment = lvalue_var._eq(&operand_var);
+            } else if operand.get_type().is_int_type() {
+                let assignment = match encoding {
+                    IntegerEncoding::Int => {
+                        // Zero extension keeps the unsigned value of the operand
+                        let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
+                        let operand_var = Int::new_const(solver.get_context(), operand_var_name);
+                        lvalue_var._eq(&get_unsigned_int(solver, &operand_var, get_int_width(&operand.get_type().as_any_type_enum())))
+                    }
+                    IntegerEncoding::BitVector => {
+                        let operand_width = get_int_width(&operand.get_type().as_any_type_enum());
+                        let lvalue_width = get_int_width(&instruction.get_type());
+                        let lvalue_var = BV::new_const(solver.get_context(), lvalue_var_name, lvalue_width);
+                        let operand_var = BV::new_const(solver.get_context(), operand_var_name, operand_width);
+                        lvalue_var._eq(&operand_var.zero_ext(lvalue_width - operand_width))
+                    }
+                };
                 node_var = assignment.implies(&node_var);
             } else {
                 warn!("Type {} is not a supported target type for the ZExt instruction!", instruction.get_type().to_string());
@@ -224,5 +311,5 @@ pub fn codegen_instruction<'a>(
             warn!("Opcode {:?} is not supported as a statement for code gen", opcode);
         }
     }
-    node_var
+    Ok(node_var)
 }
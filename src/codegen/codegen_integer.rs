@@ -0,0 +1,178 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use tracing::warn;
+
+use inkwell::types::AnyType;
+use inkwell::values::{InstructionOpcode, InstructionValue};
+
+use z3::ast::{Ast, Bool, Int, BV};
+use z3::Solver;
+
+use crate::error::WombatError;
+use crate::utils::var_utils::{get_int_width, get_operand_value, get_unsigned_int, get_var_name, get_wrapped_signed_int, IntegerEncoding};
+
+fn get_absolute_int<'a>(solver: &'a Solver<'_>, var: &Int<'a>) -> Int<'a> {
+    var.lt(&Int::from_i64(solver.get_context(), 0)).ite(&var.unary_minus(), var)
+}
+
+fn get_truncated_quotient<'a>(solver: &'a Solver<'_>, dividend: &Int<'a>, divisor: &Int<'a>) -> Int<'a> {
+    //! Z3 rounds Int division towards negative infinity, whereas LLVM rounds the quotient towards zero
+    let const_0 = Int::from_i64(solver.get_context(), 0);
+    let absolute_quotient = get_absolute_int(solver, dividend).div(&get_absolute_int(solver, divisor));
+    let is_negative_quotient = dividend.lt(&const_0).xor(&divisor.lt(&const_0));
+    is_negative_quotient.ite(&absolute_quotient.unary_minus(), &absolute_quotient)
+}
+
+fn get_bool_rvalue<'a>(solver: &'a Solver<'_>, opcode: InstructionOpcode, operand1: &Bool<'a>, operand2: &Bool<'a>) -> Option<Bool<'a>> {
+    //! Arithmetic on i1 is arithmetic modulo 2, which reduces to boolean operations
+    match opcode {
+        InstructionOpcode::Add | InstructionOpcode::Sub | InstructionOpcode::Xor => Some(operand1.xor(operand2)),
+        InstructionOpcode::Mul | InstructionOpcode::And => Some(Bool::and(solver.get_context(), &[operand1, operand2])),
+        InstructionOpcode::Or => Some(Bool::or(solver.get_context(), &[operand1, operand2])),
+        // Division by the only non-zero i1 leaves the dividend unchanged
+        InstructionOpcode::SDiv | InstructionOpcode::UDiv => Some(operand1.clone()),
+        InstructionOpcode::SRem | InstructionOpcode::URem => Some(Bool::from_bool(solver.get_context(), false)),
+        // Shifting an i1 by anything but 0 is poison
+        InstructionOpcode::Shl | InstructionOpcode::LShr | InstructionOpcode::AShr => Some(operand1.clone()),
+        _ => None,
+    }
+}
+
+fn get_bv_rvalue<'a>(opcode: InstructionOpcode, operand1: &BV<'a>, operand2: &BV<'a>) -> Option<BV<'a>> {
+    match opcode {
+        InstructionOpcode::Add => Some(operand1.bvadd(operand2)),
+        InstructionOpcode::Sub => Some(operand1.bvsub(operand2)),
+        InstructionOpcode::Mul => Some(operand1.bvmul(operand2)),
+        InstructionOpcode::SDiv => Some(operand1.bvsdiv(operand2)),
+        InstructionOpcode::UDiv => Some(operand1.bvudiv(operand2)),
+        InstructionOpcode::SRem => Some(operand1.bvsrem(operand2)),
+        InstructionOpcode::URem => Some(operand1.bvurem(operand2)),
+        InstructionOpcode::And => Some(operand1.bvand(operand2)),
+        InstructionOpcode::Or => Some(operand1.bvor(operand2)),
+        InstructionOpcode::Xor => Some(operand1.bvxor(operand2)),
+        InstructionOpcode::Shl => Some(operand1.bvshl(operand2)),
+        InstructionOpcode::LShr => Some(operand1.bvlshr(operand2)),
+        InstructionOpcode::AShr => Some(operand1.bvashr(operand2)),
+        _ => None,
+    }
+}
+
+fn get_int_rvalue<'a>(solver: &'a Solver<'_>, opcode: InstructionOpcode, operand1: &Int<'a>, operand2: &Int<'a>, width: u32) -> Option<Int<'a>> {
+    //! Ints hold the two's complement value, so every result is wrapped back into the range of its width
+    let unsigned_operand1 = get_unsigned_int(solver, operand1, width);
+    let unsigned_operand2 = get_unsigned_int(solver, operand2, width);
+    let rvalue = match opcode {
+        InstructionOpcode::Add => Int::add(solver.get_context(), &[operand1, operand2]),
+        InstructionOpcode::Sub => Int::sub(solver.get_context(), &[operand1, operand2]),
+        InstructionOpcode::Mul => Int::mul(solver.get_context(), &[operand1, operand2]),
+        InstructionOpcode::SDiv => get_truncated_quotient(solver, operand1, operand2),
+        InstructionOpcode::UDiv => unsigned_operand1.div(&unsigned_operand2),
+        InstructionOpcode::SRem => Int::sub(
+            solver.get_context(),
+            &[operand1, &Int::mul(solver.get_context(), &[operand2, &get_truncated_quotient(solver, operand1, operand2)])],
+        ),
+        InstructionOpcode::URem => unsigned_operand1.modulo(&unsigned_operand2),
+        InstructionOpcode::And | InstructionOpcode::Or | InstructionOpcode::Xor | InstructionOpcode::Shl | InstructionOpcode::LShr | InstructionOpcode::AShr => {
+            // Bitwise operations have no arithmetic counterpart, so they are computed on the bitvectors of the operands
+            let bv_rvalue = get_bv_rvalue(opcode, &BV::from_int(&unsigned_operand1, width), &BV::from_int(&unsigned_operand2, width))?;
+            bv_rvalue.to_int(true)
+        }
+        _ => return None,
+    };
+    Some(get_wrapped_signed_int(solver, &rvalue, width))
+}
+
+fn codegen_cast<'a>(mut node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver<'_>, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
+    //! Sign extension keeps the signed value of the operand and truncation keeps its low bits
+    let opcode = instruction.get_opcode();
+    let operand = get_operand_value(&instruction, 0)?;
+    let operand_type = operand.get_type().as_any_type_enum();
+    let lvalue_type = instruction.get_type();
+    if !operand_type.is_int_type() || !lvalue_type.is_int_type() {
+        warn!("Type {} is not a supported target type for the {:?} instruction!", lvalue_type.to_string(), opcode);
+        return Ok(node_var);
+    }
+    let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let operand_var_name = get_var_name(&operand, solver, namespace, encoding)?;
+    let operand_width = get_int_width(&operand_type);
+    let lvalue_width = get_int_width(&lvalue_type);
+
+    let assignment = if operand_width == 1 {
+        // Sign extending true gives all ones, which is -1
+        let operand_var = Bool::new_const(solver.get_context(), operand_var_name);
+        match encoding {
+            IntegerEncoding::Int => Int::new_const(solver.get_context(), lvalue_var_name)._eq(&operand_var.ite(&Int::from_i64(solver.get_context(), -1), &Int::from_i64(solver.get_context(), 0))),
+            IntegerEncoding::BitVector => BV::new_const(solver.get_context(), lvalue_var_name, lvalue_width)
+                ._eq(&operand_var.ite(&BV::from_i64(solver.get_context(), -1, lvalue_width), &BV::from_i64(solver.get_context(), 0, lvalue_width))),
+        }
+    } else {
+        match encoding {
+            IntegerEncoding::Int => {
+                let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
+                let operand_var = Int::new_const(solver.get_context(), operand_var_name);
+                if opcode == InstructionOpcode::SExt {
+                    lvalue_var._eq(&operand_var)
+                } else {
+                    lvalue_var._eq(&get_wrapped_signed_int(solver, &operand_var, lvalue_width))
+                }
+            }
+            IntegerEncoding::BitVector => {
+                let lvalue_var = BV::new_const(solver.get_context(), lvalue_var_name, lvalue_width);
+                let operand_var = BV::new_const(solver.get_context(), operand_var_name, operand_width);
+                if opcode == InstructionOpcode::SExt {
+                    lvalue_var._eq(&operand_var.sign_ext(lvalue_width - operand_width))
+                } else {
+                    lvalue_var._eq(&operand_var.extract(lvalue_width - 1, 0))
+                }
+            }
+        }
+    };
+    node_var = assignment.implies(&node_var);
+    Ok(node_var)
+}
+
+pub fn codegen_integer_instruction<'a>(mut node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver<'_>, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
+    //! Integer arithmetic, bitwise and shift instructions wrap on overflow, as poison from nsw/nuw/exact flags is not modelled
+    let opcode = instruction.get_opcode();
+    if matches!(opcode, InstructionOpcode::SExt | InstructionOpcode::Trunc) {
+        return codegen_cast(node_var, instruction, solver, namespace, encoding);
+    }
+
+    let lvalue_type = instruction.get_type();
+    if !lvalue_type.is_int_type() {
+        warn!("Currently unsupported type {:?} for {:?} operand", lvalue_type.to_string(), opcode);
+        return Ok(node_var);
+    }
+    let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let operand1_var_name = get_var_name(&get_operand_value(&instruction, 0)?, solver, namespace, encoding)?;
+    let operand2_var_name = get_var_name(&get_operand_value(&instruction, 1)?, solver, namespace, encoding)?;
+    let width = get_int_width(&lvalue_type);
+
+    let assignment = if width == 1 {
+        let lvalue_var = Bool::new_const(solver.get_context(), lvalue_var_name);
+        let operand1_var = Bool::new_const(solver.get_context(), operand1_var_name);
+        let operand2_var = Bool::new_const(solver.get_context(), operand2_var_name);
+        get_bool_rvalue(solver, opcode, &operand1_var, &operand2_var).map(|rvalue| lvalue_var._eq(&rvalue))
+    } else {
+        match encoding {
+            IntegerEncoding::Int => {
+                let lvalue_var = Int::new_const(solver.get_context(), lvalue_var_name);
+                let operand1_var = Int::new_const(solver.get_context(), operand1_var_name);
+                let operand2_var = Int::new_const(solver.get_context(), operand2_var_name);
+                get_int_rvalue(solver, opcode, &operand1_var, &operand2_var, width).map(|rvalue| lvalue_var._eq(&rvalue))
+            }
+            IntegerEncoding::BitVector => {
+                let lvalue_var = BV::new_const(solver.get_context(), lvalue_var_name, width);
+                let operand1_var = BV::new_const(solver.get_context(), operand1_var_name, width);
+                let operand2_var = BV::new_const(solver.get_context(), operand2_var_name, width);
+                get_bv_rvalue(opcode, &operand1_var, &operand2_var).map(|rvalue| lvalue_var._eq(&rvalue))
+            }
+        }
+    };
+    match assignment {
+        Some(assignment) => node_var = assignment.implies(&node_var),
+        None => warn!("Opcode {:?} is not supported as a statement for code gen", opcode),
+    }
+    Ok(node_var)
+}
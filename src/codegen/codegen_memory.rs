@@ -0,0 +1,471 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::warn;
+
+use inkwell::module::Module as InkwellModule;
+use inkwell::targets::TargetData;
+use inkwell::types::{AnyType, AnyTypeEnum};
+use inkwell::values::{AnyValue, BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue};
+
+use z3::ast::{Array, Ast, Bool, Dynamic, Int, BV};
+use z3::{Solver, Sort};
+
+use crate::codegen::codegen_call::is_general_call;
+use crate::codegen::codegen_float::{get_loaded_float_bits, get_stored_float_bits};
+use crate::codegen::codegen_summary::is_summary_namespace;
+use crate::error::WombatError;
+use crate::utils::function_utils::{get_function_by_name, get_function_name};
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::resolve_phi_to_dsa::PHI_ALLOCA_SUFFIX;
+use crate::utils::unroll_loops::HAVOC_MEMORY_FUNCTION_NAME;
+use crate::utils::var_utils::{
+    get_aggregate_leaves, get_assignment, get_block_name, get_called_function, get_field_var_name, get_instruction_string, get_int_from_i128, get_int_width, get_operand_value, get_typed_var,
+    get_var_name, get_wrapped_signed_int, IntegerEncoding,
+};
+
+pub const MEMORY_NAME: &str = "memory";
+// Every allocation gets its own region of addresses so distinct allocations never alias
+pub const REGION_SIZE: u64 = 1 << 32;
+// Suffix of the variable holding the region base of an allocation in a function summary
+const REGION_VAR_SUFFIX: &str = ".wombat_symx_region";
+// Longest memcpy/memset (in bytes) that is modelled precisely, longer ones leave the memory unconstrained
+const MAX_MEMORY_INTRINSIC_LENGTH: u64 = 1024;
+
+pub fn get_new_region_base<'a>(module: &AnalysedModule, solver: &'a Solver<'_>) -> Int<'a> {
+    //! Returns the base address of a fresh region, numbered per analysis so the addresses do not depend on earlier analyses
+    get_int_from_i128(solver, i128::from(module.get_next_region()) * i128::from(REGION_SIZE))
+}
+
+pub fn is_region_var_name(var_name: &str) -> bool {
+    var_name.ends_with(REGION_VAR_SUFFIX)
+}
+
+pub fn get_memory_var<'a>(solver: &'a Solver<'_>, memory_name: &str) -> Array<'a> {
+    //! Memory maps addresses to cells that each hold a whole scalar, modelled as an Int in both encodings
+    Array::new_const(solver.get_context(), memory_name, &Sort::int(solver.get_context()), &Sort::int(solver.get_context()))
+}
+
+fn is_phi_alloca_instruction(instruction: &InstructionValue) -> bool {
+    //! Allocas introduced when resolving phi instructions are stored at most once per path and stay modelled by name
+    instruction.get_opcode() == InstructionOpcode::Alloca && instruction.get_name().map_or(false, |name| name.to_string_lossy().ends_with(PHI_ALLOCA_SUFFIX))
+}
+
+pub fn is_phi_alloca(pointer: &BasicValueEnum) -> bool {
+    pointer.as_instruction_value().map_or(false, |instruction| is_phi_alloca_instruction(&instruction))
+}
+
+fn get_called_function_name(module: &InkwellModule, instruction: &InstructionValue) -> Option<String> {
+    //! Calls through function pointers name no function and are rejected once the call is encoded
+    get_called_function(module, instruction).ok().map(|call_operand| get_function_name(&call_operand))
+}
+
+fn is_memory_intrinsic(function_name: &str) -> bool {
+    function_name.starts_with("llvm.memcpy") || function_name.starts_with("llvm.memmove") || function_name.starts_with("llvm.memset")
+}
+
+fn is_direct_memory_access(module: &InkwellModule, instruction: &InstructionValue) -> bool {
+    match instruction.get_opcode() {
+        InstructionOpcode::Load => get_operand_value(instruction, 0).map_or(false, |pointer| !is_phi_alloca(&pointer)),
+        InstructionOpcode::Store => get_operand_value(instruction, 1).map_or(false, |pointer| !is_phi_alloca(&pointer)),
+        InstructionOpcode::Call => get_called_function_name(module, instruction).map_or(false, |called_function_name| {
+            is_memory_intrinsic(&called_function_name) || called_function_name.eq(HAVOC_MEMORY_FUNCTION_NAME)
+        }),
+        _ => false,
+    }
+}
+
+fn get_callee_name(module: &InkwellModule, called_function_name: &str) -> Option<String> {
+    //! Name of the function a general call is inlined from
+    if !is_general_call(module, called_function_name) {
+        return None;
+    }
+    get_function_by_name(module, &String::from(called_function_name)).map(|callee| get_function_name(&callee.as_global_value().as_pointer_value()))
+}
+
+pub fn get_memory_functions(module: &InkwellModule) -> HashSet<String> {
+    //! Names of the functions that read or write memory themselves or through a function they call,
+    //! no memory needs to be threaded through any other function
+    let mut callers = HashMap::<String, Vec<String>>::new();
+    let mut worklist = Vec::<String>::new();
+    let mut next_function = module.get_first_function();
+    while let Some(current_function) = next_function {
+        let function_name = get_function_name(&current_function.as_global_value().as_pointer_value());
+        for bb in current_function.get_basic_blocks() {
+            let mut next_instruction = bb.get_first_instruction();
+            while let Some(current_instruction) = next_instruction {
+                if is_direct_memory_access(module, &current_instruction) {
+                    worklist.push(function_name.clone());
+                } else if current_instruction.get_opcode() == InstructionOpcode::Call {
+                    if let Some(callee_name) = get_called_function_name(module, &current_instruction).and_then(|called_function_name| get_callee_name(module, &called_function_name)) {
+                        callers.entry(callee_name).or_default().push(function_name.clone());
+                    }
+                }
+                next_instruction = current_instruction.get_next_instruction();
+            }
+        }
+        next_function = current_function.get_next_function();
+    }
+
+    // Callers of a function that uses memory use it as well
+    let mut memory_functions = HashSet::<String>::new();
+    while let Some(function_name) = worklist.pop() {
+        if memory_functions.insert(function_name.clone()) {
+            worklist.extend(callers.get(&function_name).into_iter().flatten().cloned());
+        }
+    }
+    memory_functions
+}
+
+pub fn is_memory_write(module: &InkwellModule, memory_functions: &HashSet<String>, instruction: &InstructionValue) -> bool {
+    //! Instructions that create a new version of the memory
+    match instruction.get_opcode() {
+        InstructionOpcode::Store => get_operand_value(instruction, 1).map_or(false, |pointer| !is_phi_alloca(&pointer)),
+        InstructionOpcode::Call => get_called_function_name(module, instruction).map_or(false, |called_function_name| {
+            if is_memory_intrinsic(&called_function_name) || called_function_name.eq(HAVOC_MEMORY_FUNCTION_NAME) {
+                true
+            } else {
+                get_callee_name(module, &called_function_name).map_or(false, |callee_name| memory_functions.contains(&callee_name))
+            }
+        }),
+        _ => false,
+    }
+}
+
+pub fn get_block_memory_name(node: &str, version: usize) -> String {
+    //! Memory versions are numbered by the writes preceding them in a basic block, starting at 0 on block entry
+    format!("{}.{}.{}", node, MEMORY_NAME, version)
+}
+
+pub fn get_return_memory_name(return_register: &str) -> String {
+    //! Memory a function returns with, named after the return register of its call
+    format!("{}.{}", return_register, MEMORY_NAME)
+}
+
+pub fn get_block_exit_memory_name(module: &AnalysedModule, node: &str, last_instruction: Option<InstructionValue>) -> String {
+    let mut version = 0;
+    let mut prev_instruction = last_instruction;
+    while let Some(current_instruction) = prev_instruction {
+        if is_memory_write(module, module.get_memory_functions(), &current_instruction) {
+            version += 1;
+        }
+        prev_instruction = current_instruction.get_previous_instruction();
+    }
+    get_block_memory_name(node, version)
+}
+
+pub fn get_memory_name(module: &AnalysedModule, instruction: &InstructionValue, namespace: &str) -> Result<String, WombatError> {
+    //! Name of the memory version read by the instruction
+    let node = format!("{}{}", namespace, get_block_name(instruction)?);
+    Ok(get_block_exit_memory_name(module, &node, instruction.get_previous_instruction()))
+}
+
+pub fn get_next_memory_name(module: &AnalysedModule, instruction: &InstructionValue, namespace: &str) -> Result<String, WombatError> {
+    //! Name of the memory version written by the instruction
+    let node = format!("{}{}", namespace, get_block_name(instruction)?);
+    Ok(get_block_exit_memory_name(module, &node, Some(*instruction)))
+}
+
+fn get_pointer_var<'a>(solver: &'a Solver<'_>, pointer: &dyn AnyValue, namespace: &str, encoding: IntegerEncoding) -> Result<Int<'a>, WombatError> {
+    Ok(Int::new_const(solver.get_context(), get_var_name(pointer, solver, namespace, encoding)?))
+}
+
+fn get_index_var<'a>(solver: &'a Solver<'_>, index: &BasicValueEnum, namespace: &str, encoding: IntegerEncoding) -> Result<Int<'a>, WombatError> {
+    //! GEP indices are signed, so bitvector indices are converted to their signed value
+    let index_name = get_var_name(index, solver, namespace, encoding)?;
+    Ok(match encoding {
+        IntegerEncoding::Int => Int::new_const(solver.get_context(), index_name),
+        IntegerEncoding::BitVector => BV::new_const(solver.get_context(), index_name, get_int_width(&index.get_type().as_any_type_enum())).to_int(true),
+    })
+}
+
+pub fn get_leaf_offset(target_data: &TargetData, var_type: &AnyTypeEnum, path: &[u32]) -> u64 {
+    //! Byte offset of the scalar field at the index path of an aggregate given the data layout of the module
+    let mut offset = 0;
+    let mut field_type = *var_type;
+    for index in path {
+        match field_type {
+            AnyTypeEnum::StructType(struct_type) => {
+                offset += target_data.offset_of_element(&struct_type, *index).unwrap();
+                field_type = struct_type.get_field_type_at_index(*index).unwrap().as_any_type_enum();
+            }
+            AnyTypeEnum::ArrayType(array_type) => {
+                field_type = array_type.get_element_type().as_any_type_enum();
+                offset += u64::from(*index) * target_data.get_abi_size(&field_type);
+            }
+            _ => break,
+        }
+    }
+    offset
+}
+
+fn get_leaf_address<'a>(solver: &'a Solver<'_>, target_data: &TargetData, address: &Int<'a>, var_type: &AnyTypeEnum, path: &[u32]) -> Int<'a> {
+    Int::add(solver.get_context(), &[address, &Int::from_u64(solver.get_context(), get_leaf_offset(target_data, var_type, path))])
+}
+
+fn to_memory_value<'a>(solver: &'a Solver<'_>, value_name: &str, value_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<Int<'a>> {
+    //! Convert a scalar into the Int held by a memory cell
+    if value_type.is_float_type() {
+        return get_stored_float_bits(solver, value_name, value_type);
+    }
+    let value = get_typed_var(solver, value_name, value_type, encoding)?;
+    if let Some(bool_value) = value.as_bool() {
+        Some(bool_value.ite(&Int::from_i64(solver.get_context(), 1), &Int::from_i64(solver.get_context(), 0)))
+    } else if let Some(bv_value) = value.as_bv() {
+        Some(bv_value.to_int(false))
+    } else {
+        value.as_int()
+    }
+}
+
+pub fn get_memory_cell_value<'a>(solver: &'a Solver<'_>, cell: &Int<'a>, value_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<Dynamic<'a>> {
+    //! Convert the Int held by a memory cell back into a scalar of the given type,
+    //! truncating it like a load of a narrower type does since a cell may hold any Int
+    if value_type.is_pointer_type() {
+        Some(Dynamic::from_ast(cell))
+    } else if !value_type.is_int_type() {
+        None
+    } else if get_int_width(value_type) == 1 {
+        Some(Dynamic::from_ast(&cell._eq(&Int::from_i64(solver.get_context(), 0)).not()))
+    } else if encoding == IntegerEncoding::BitVector {
+        Some(Dynamic::from_ast(&BV::from_int(cell, get_int_width(value_type))))
+    } else {
+        Some(Dynamic::from_ast(&get_wrapped_signed_int(solver, cell, get_int_width(value_type))))
+    }
+}
+
+fn from_memory_value<'a>(solver: &'a Solver<'_>, lvalue_name: &str, cell: &Int<'a>, value_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<Bool<'a>> {
+    //! Assign a memory cell to a scalar, which holds a value of the scalar's type whatever the cell holds,
+    //! so uninitialized memory (such as the pointee of an input reference) or a wider stored value never rules out the path
+    if value_type.is_float_type() {
+        return get_loaded_float_bits(solver, lvalue_name, value_type).map(|bits| bits._eq(cell));
+    }
+    let lvalue = get_typed_var(solver, lvalue_name, value_type, encoding)?;
+    Some(lvalue._eq(&get_memory_cell_value(solver, cell, value_type, encoding)?))
+}
+
+pub fn codegen_alloca<'a>(
+    module: &AnalysedModule,
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+) -> Result<Bool<'a>, WombatError> {
+    //! Point the allocation to a fresh region
+    if is_phi_alloca_instruction(&instruction) {
+        return Ok(node_var);
+    }
+    let pointer_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let region_base = if is_summary_namespace(namespace) {
+        // A summary is instantiated at several call sites, which each assign the allocation a fresh region
+        Int::new_const(solver.get_context(), format!("{}{}", pointer_var_name, REGION_VAR_SUFFIX))
+    } else {
+        get_new_region_base(module, solver)
+    };
+    let assignment = Int::new_const(solver.get_context(), pointer_var_name)._eq(&region_base);
+    Ok(assignment.implies(&node_var))
+}
+
+pub fn codegen_load<'a>(
+    module: &AnalysedModule,
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+) -> Result<Bool<'a>, WombatError> {
+    //! Aggregates are loaded field by field from the cells at the offsets of their scalar fields
+    let pointer = get_operand_value(&instruction, 0)?;
+    let lvalue_var_name = get_var_name(&instruction, solver, namespace, encoding)?;
+    let value_type = instruction.get_type();
+    if is_phi_alloca(&pointer) {
+        // Resolved phi values are equated with their alloca by name
+        let rvalue_var_name = get_var_name(&pointer, solver, namespace, encoding)?;
+        return if let Some(assignment) = get_assignment(solver, &lvalue_var_name, &rvalue_var_name, &value_type, encoding) {
+            Ok(assignment.implies(&node_var))
+        } else {
+            warn!("Currently unsupported type {:?} for load operand", value_type.to_string());
+            Ok(node_var)
+        };
+    }
+
+    let target_data = module.get_target_data();
+    let memory_var = get_memory_var(solver, &get_memory_name(module, &instruction, namespace)?);
+    let address = get_pointer_var(solver, &pointer, namespace, encoding)?;
+    let mut assignments = Vec::<Bool>::new();
+    for (path, leaf_type) in get_aggregate_leaves(&value_type) {
+        let cell = memory_var.select(&get_leaf_address(solver, target_data, &address, &value_type, &path)).as_int().unwrap();
+        if let Some(assignment) = from_memory_value(solver, &get_field_var_name(&lvalue_var_name, &path), &cell, &leaf_type, encoding) {
+            assignments.push(assignment);
+        } else {
+            warn!("Currently unsupported type {:?} for load operand", value_type.to_string());
+            return Ok(node_var);
+        }
+    }
+    let assignment_refs: Vec<&Bool> = assignments.iter().collect();
+    Ok(Bool::and(solver.get_context(), &assignment_refs).implies(&node_var))
+}
+
+pub fn codegen_store<'a>(
+    module: &AnalysedModule,
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+) -> Result<Bool<'a>, WombatError> {
+    //! Aggregates are stored field by field to the cells at the offsets of their scalar fields
+    let value = get_operand_value(&instruction, 0)?;
+    let pointer = get_operand_value(&instruction, 1)?;
+    let value_name = get_var_name(&value, solver, namespace, encoding)?;
+    let value_type = value.get_type().as_any_type_enum();
+    if is_phi_alloca(&pointer) {
+        // Resolved phi values are equated with their alloca by name
+        let pointer_name = get_var_name(&pointer, solver, namespace, encoding)?;
+        return if let Some(assignment) = get_assignment(solver, &pointer_name, &value_name, &value_type, encoding) {
+            Ok(assignment.implies(&node_var))
+        } else {
+            warn!("Currently unsupported type {:?} for store operand", value.get_type().to_string());
+            Ok(node_var)
+        };
+    }
+
+    let target_data = module.get_target_data();
+    let memory_var = get_memory_var(solver, &get_memory_name(module, &instruction, namespace)?);
+    let next_memory_var = get_memory_var(solver, &get_next_memory_name(module, &instruction, namespace)?);
+    let address = get_pointer_var(solver, &pointer, namespace, encoding)?;
+    let mut rvalue_var = memory_var;
+    for (path, leaf_type) in get_aggregate_leaves(&value_type) {
+        if let Some(cell) = to_memory_value(solver, &get_field_var_name(&value_name, &path), &leaf_type, encoding) {
+            rvalue_var = rvalue_var.store(&get_leaf_address(solver, target_data, &address, &value_type, &path), &cell);
+        } else {
+            // The stored value is unknown, so the memory after the store is left unconstrained
+            warn!("Currently unsupported type {:?} for store operand", value.get_type().to_string());
+            return Ok(node_var);
+        }
+    }
+    let assignment = next_memory_var._eq(&rvalue_var);
+    Ok(assignment.implies(&node_var))
+}
+
+pub fn codegen_get_element_ptr<'a>(
+    module: &AnalysedModule,
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+) -> Result<Bool<'a>, WombatError> {
+    //! Offset the base pointer by the byte offset of the indexed element given the data layout of the module
+    let target_data = module.get_target_data();
+    let pointer = get_operand_value(&instruction, 0)?;
+    let mut address = get_pointer_var(solver, &pointer, namespace, encoding)?;
+    let mut indexed_type = pointer.get_type().into_pointer_type().get_element_type();
+
+    for i in 1..instruction.get_num_operands() {
+        let index = get_operand_value(&instruction, i)?;
+        let offset = if i == 1 {
+            // The first index steps over whole elements of the pointed to type
+            let element_size = Int::from_u64(solver.get_context(), target_data.get_abi_size(&indexed_type));
+            Int::mul(solver.get_context(), &[&get_index_var(solver, &index, namespace, encoding)?, &element_size])
+        } else {
+            match indexed_type {
+                AnyTypeEnum::ArrayType(array_type) => {
+                    indexed_type = array_type.get_element_type().as_any_type_enum();
+                    let element_size = Int::from_u64(solver.get_context(), target_data.get_abi_size(&indexed_type));
+                    Int::mul(solver.get_context(), &[&get_index_var(solver, &index, namespace, encoding)?, &element_size])
+                }
+                AnyTypeEnum::VectorType(vector_type) => {
+                    indexed_type = vector_type.get_element_type().as_any_type_enum();
+                    let element_size = Int::from_u64(solver.get_context(), target_data.get_abi_size(&indexed_type));
+                    Int::mul(solver.get_context(), &[&get_index_var(solver, &index, namespace, encoding)?, &element_size])
+                }
+                AnyTypeEnum::StructType(struct_type) => {
+                    // Struct fields are always indexed by constants
+                    let field = index
+                        .into_int_value()
+                        .get_zero_extended_constant()
+                        .and_then(|field_index| u32::try_from(field_index).ok())
+                        .and_then(|field_index| Some((struct_type.get_field_type_at_index(field_index)?, target_data.offset_of_element(&struct_type, field_index)?)));
+                    let (field_type, field_offset) = field.ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(&instruction)))?;
+                    indexed_type = field_type.as_any_type_enum();
+                    Int::from_u64(solver.get_context(), field_offset)
+                }
+                _ => {
+                    warn!("Currently unsupported type {:?} for get element pointer", indexed_type.to_string());
+                    return Ok(node_var);
+                }
+            }
+        };
+        address = Int::add(solver.get_context(), &[&address, &offset]);
+    }
+
+    let lvalue_var = get_pointer_var(solver, &instruction, namespace, encoding)?;
+    let assignment = lvalue_var._eq(&address);
+    Ok(assignment.implies(&node_var))
+}
+
+pub fn codegen_memory_intrinsic<'a>(
+    module: &AnalysedModule,
+    node_var: Bool<'a>,
+    instruction: InstructionValue,
+    solver: &'a Solver<'_>,
+    namespace: &str,
+    encoding: IntegerEncoding,
+) -> Result<Bool<'a>, WombatError> {
+    //! Copy or clear every cell of a llvm.memcpy/llvm.memmove/llvm.memset of constant length
+    let called_function_name = get_function_name(&get_called_function(module, &instruction)?);
+    let destination = get_pointer_var(solver, &get_operand_value(&instruction, 0)?, namespace, encoding)?;
+    let length = get_operand_value(&instruction, 2)?.into_int_value().get_zero_extended_constant();
+    let length = match length {
+        Some(length) if length <= MAX_MEMORY_INTRINSIC_LENGTH => length,
+        _ => {
+            warn!("Currently unsupported length {:?} for {}, the memory is left unconstrained", length, called_function_name);
+            return Ok(node_var);
+        }
+    };
+
+    let memory_var = get_memory_var(solver, &get_memory_name(module, &instruction, namespace)?);
+    let mut rvalue_var = memory_var.clone();
+    if called_function_name.starts_with("llvm.memset") {
+        // Cells hold whole scalars, so only clearing memory can be modelled byte by byte
+        let value = get_operand_value(&instruction, 1)?.into_int_value().get_zero_extended_constant();
+        if value != Some(0) {
+            warn!("Currently unsupported non-zero value {:?} for {}, the memory is left unconstrained", value, called_function_name);
+            return Ok(node_var);
+        }
+        for offset in 0..length {
+            let address = Int::add(solver.get_context(), &[&destination, &Int::from_u64(solver.get_context(), offset)]);
+            rvalue_var = rvalue_var.store(&address, &Int::from_i64(solver.get_context(), 0));
+        }
+    } else {
+        // Scalars are stored at their first byte, so copying every byte offset copies every scalar
+        let source = get_pointer_var(solver, &get_operand_value(&instruction, 1)?, namespace, encoding)?;
+        for offset in 0..length {
+            let offset_var = Int::from_u64(solver.get_context(), offset);
+            let source_address = Int::add(solver.get_context(), &[&source, &offset_var]);
+            let destination_address = Int::add(solver.get_context(), &[&destination, &offset_var]);
+            rvalue_var = rvalue_var.store(&destination_address, &memory_var.select(&source_address));
+        }
+    }
+
+    let next_memory_var = get_memory_var(solver, &get_next_memory_name(module, &instruction, namespace)?);
+    let assignment = next_memory_var._eq(&rvalue_var);
+    Ok(assignment.implies(&node_var))
+}
+
+pub fn codegen_bit_cast<'a>(node_var: Bool<'a>, instruction: InstructionValue, solver: &'a Solver<'_>, namespace: &str, encoding: IntegerEncoding) -> Result<Bool<'a>, WombatError> {
+    //! Pointer casts keep the address
+    let operand = get_operand_value(&instruction, 0)?;
+    if !operand.get_type().is_pointer_type() || !instruction.get_type().is_pointer_type() {
+        warn!("Currently unsupported type {:?} for bit cast", operand.get_type().to_string());
+        return Ok(node_var);
+    }
+    let lvalue_var = get_pointer_var(solver, &instruction, namespace, encoding)?;
+    let rvalue_var = get_pointer_var(solver, &operand, namespace, encoding)?;
+    let assignment = lvalue_var._eq(&rvalue_var);
+    Ok(assignment.implies(&node_var))
+}
@@ -0,0 +1,152 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use inkwell::values::FunctionValue;
+
+use z3::ast::{Ast, Bool, Dynamic};
+use z3::{DeclKind, FuncDecl, Solver};
+
+use crate::codegen::codegen_function::codegen_function;
+use crate::codegen::codegen_memory::{get_new_region_base, is_region_var_name};
+use crate::error::WombatError;
+use crate::symbolic_execution::MAIN_FUNCTION_RETURN_REGISTER;
+use crate::utils::function_utils::get_function_name;
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::var_utils::IntegerEncoding;
+
+pub const SUMMARY_NAMESPACE: &str = "summary.";
+
+/// Encoding of a function computed once, simplified, and instantiated at each call site by renaming its constants
+pub struct FunctionSummary<'ctx> {
+    namespace: String,
+    post_node_name: String,
+    return_register: String,
+    constants: Vec<(String, Dynamic<'ctx>)>,
+    assertions: Vec<Bool<'ctx>>,
+}
+
+pub type SummaryCache<'ctx> = RefCell<HashMap<String, FunctionSummary<'ctx>>>;
+
+pub fn is_summary_namespace(namespace: &str) -> bool {
+    //! Namespaces of a function encoded for its summary, including the functions inlined into it
+    namespace.starts_with(SUMMARY_NAMESPACE)
+}
+
+fn get_constants<'ctx>(assertions: &[Bool<'ctx>]) -> Vec<(String, Dynamic<'ctx>)> {
+    //! Collect the constants the assertions are stated over, visiting each shared subterm once
+    let mut constants = Vec::new();
+    let mut visited = HashSet::<Dynamic>::new();
+    let mut worklist: Vec<Dynamic> = assertions.iter().map(Dynamic::from_ast).collect();
+    while let Some(term) = worklist.pop() {
+        if !visited.insert(term.clone()) {
+            continue;
+        }
+        if term.is_const() && term.decl().kind() == DeclKind::UNINTERPRETED {
+            constants.push((term.decl().name(), term));
+        } else {
+            worklist.extend(term.children());
+        }
+    }
+    constants
+}
+
+fn compute_function_summary<'ctx>(
+    module: &AnalysedModule,
+    function: &FunctionValue,
+    solver: &Solver<'ctx>,
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: &SummaryCache<'ctx>,
+) -> Result<FunctionSummary<'ctx>, WombatError> {
+    //! Encode the function once in a scratch solver under a namespace reserved for its summary,
+    //! simplified once so every call site asserts the simplified formula instead of encoding the function again
+    let function_name = get_function_name(&function.as_global_value().as_pointer_value());
+    let namespace = format!("{}{}.", SUMMARY_NAMESPACE, function_name);
+    let post_node_name = format!("{}wombat_symx_post_node", namespace);
+    let return_register = format!("{}{}", namespace, MAIN_FUNCTION_RETURN_REGISTER);
+
+    let summary_solver = Solver::new(solver.get_context());
+    codegen_function(
+        module,
+        function,
+        &summary_solver,
+        &namespace,
+        function
+            .get_name()
+            .to_str()
+            .map_err(|_| WombatError::InvalidModule(format!("name of {} is not valid UTF-8", function_name)))?,
+        &post_node_name,
+        &return_register,
+        encoding,
+        recursion_bound,
+        Some(summaries),
+    )?;
+    debug!("Summary of {}:\n{:?}", function_name, summary_solver);
+
+    let assertions: Vec<Bool> = summary_solver.get_assertions().iter().map(|assertion| assertion.simplify()).collect();
+    Ok(FunctionSummary {
+        constants: get_constants(&assertions),
+        assertions,
+        namespace,
+        post_node_name,
+        return_register,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn instantiate_function_summary<'ctx>(
+    module: &AnalysedModule,
+    function: &FunctionValue,
+    solver: &Solver<'ctx>,
+    namespace: &str,
+    return_target_node: &str,
+    return_register: &str,
+    encoding: IntegerEncoding,
+    recursion_bound: u32,
+    summaries: &SummaryCache<'ctx>,
+) -> Result<(), WombatError> {
+    //! Assert the summary of a function as if it was inlined with the given namespace, computing the summary on first use
+    let function_name = get_function_name(&function.as_global_value().as_pointer_value());
+    if !summaries.borrow().contains_key(&function_name) {
+        let summary = compute_function_summary(module, function, solver, encoding, recursion_bound, summaries)?;
+        summaries.borrow_mut().insert(function_name.clone(), summary);
+    }
+
+    let summaries = summaries.borrow();
+    let summary = summaries.get(&function_name).ok_or_else(|| WombatError::FunctionNotFound(function_name.clone()))?;
+
+    // Rename every constant of the summary to its counterpart for this call site
+    let mut substitutions = Vec::<(Dynamic, Dynamic)>::new();
+    for (name, constant) in &summary.constants {
+        let instance_name = if name.eq(&summary.post_node_name) {
+            String::from(return_target_node)
+        } else if let Some(return_field) = name.strip_prefix(&summary.return_register) {
+            // Fields of aggregate return values and the returned memory are named after the return register
+            format!("{}{}", return_register, return_field)
+        } else if let Some(local_name) = name.strip_prefix(&summary.namespace) {
+            format!("{}{}", namespace, local_name)
+        } else {
+            // Global variables such as the panic flag are shared with the caller
+            continue;
+        };
+        let instance = FuncDecl::new(solver.get_context(), instance_name.as_str(), &[], &constant.get_sort()).apply(&[]);
+        if is_region_var_name(&instance_name) && !is_summary_namespace(namespace) {
+            // Each call site allocates its own regions, unless it is itself part of a summary which leaves that to its own call sites
+            if let Some(region_var) = instance.as_int() {
+                solver.assert(&region_var._eq(&get_new_region_base(module, solver)));
+            }
+        }
+        substitutions.push((constant.clone(), instance));
+    }
+    let substitution_refs: Vec<(&Dynamic, &Dynamic)> = substitutions.iter().map(|(from, to)| (from, to)).collect();
+
+    for assertion in &summary.assertions {
+        solver.assert(&assertion.substitute(&substitution_refs));
+    }
+    Ok(())
+}
@@ -1,4 +1,8 @@
 pub mod codegen_basic_block;
 pub mod codegen_call;
+pub mod codegen_float;
 pub mod codegen_function;
 pub mod codegen_instruction;
+pub mod codegen_integer;
+pub mod codegen_memory;
+pub mod codegen_summary;
@@ -0,0 +1,157 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::path::Path;
+
+use crate::codegen::codegen_call::DEFAULT_RECURSION_BOUND;
+use crate::error::WombatError;
+use crate::report::OutputFormat;
+use crate::utils::unroll_loops::DEFAULT_UNROLL_BOUND;
+use crate::utils::var_utils::IntegerEncoding;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    /// Rust source file, compiled to bytecode next to it before the analysis
+    Source,
+    /// Rust source file whose bytecode was already compiled next to it, as in benchmark mode
+    PrecompiledSource,
+    /// LLVM bitcode (.bc) or textual IR (.ll) analysed without a Rust source file, so counterexamples are not replayed
+    Bytecode,
+}
+
+impl InputKind {
+    pub fn from_file_name(file_name: &str) -> Self {
+        //! Bitcode and IR files are analysed directly, anything else is compiled as a Rust source file
+        match Path::new(file_name).extension().and_then(|extension| extension.to_str()) {
+            Some("bc") | Some("ll") => InputKind::Bytecode,
+            _ => InputKind::Source,
+        }
+    }
+}
+
+/// Options of an analysis, built by chaining setters on the default configuration
+///
+/// ```ignore
+/// let config = AnalysisConfig::new().encoding(IntegerEncoding::BitVector).unroll_bound(10).replay(false);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnalysisConfig {
+    /// Kind of the input file, derived from its extension when not set
+    pub input_kind: Option<InputKind>,
+    /// Milliseconds the solver may spend on each check before giving up with an unknown verdict
+    pub solver_timeout_ms: Option<u64>,
+    pub encoding: IntegerEncoding,
+    /// Maximum number of times a loop header is entered before the execution is cut off as bound exhausted, at least 1
+    pub unroll_bound: u32,
+    /// Maximum number of nested recursive calls inlined before the execution is cut off as bound exhausted
+    pub recursion_bound: u32,
+    pub use_summaries: bool,
+    pub enumerate_panics: bool,
+    /// Maximum number of distinct counterexamples for each reachable panic
+    pub counterexample_count: u32,
+    pub minimize: bool,
+    /// Call the function with each counterexample to capture the actual error, which by default is done whenever a Rust source is available
+    pub replay: Option<bool>,
+    pub format: OutputFormat,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            input_kind: None,
+            solver_timeout_ms: None,
+            encoding: IntegerEncoding::Int,
+            unroll_bound: DEFAULT_UNROLL_BOUND,
+            recursion_bound: DEFAULT_RECURSION_BOUND,
+            use_summaries: false,
+            enumerate_panics: false,
+            counterexample_count: 1,
+            minimize: false,
+            replay: None,
+            format: OutputFormat::Text,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    pub fn new() -> Self {
+        AnalysisConfig::default()
+    }
+
+    pub fn input_kind(mut self, input_kind: InputKind) -> Self {
+        self.input_kind = Some(input_kind);
+        self
+    }
+
+    pub fn solver_timeout_ms(mut self, solver_timeout_ms: Option<u64>) -> Self {
+        self.solver_timeout_ms = solver_timeout_ms;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: IntegerEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn unroll_bound(mut self, unroll_bound: u32) -> Self {
+        self.unroll_bound = unroll_bound;
+        self
+    }
+
+    pub fn recursion_bound(mut self, recursion_bound: u32) -> Self {
+        self.recursion_bound = recursion_bound;
+        self
+    }
+
+    pub fn use_summaries(mut self, use_summaries: bool) -> Self {
+        self.use_summaries = use_summaries;
+        self
+    }
+
+    pub fn enumerate_panics(mut self, enumerate_panics: bool) -> Self {
+        self.enumerate_panics = enumerate_panics;
+        self
+    }
+
+    pub fn counterexample_count(mut self, counterexample_count: u32) -> Self {
+        self.counterexample_count = counterexample_count;
+        self
+    }
+
+    pub fn minimize(mut self, minimize: bool) -> Self {
+        self.minimize = minimize;
+        self
+    }
+
+    pub fn replay(mut self, replay: bool) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn get_input_kind(&self, file_name: &str) -> InputKind {
+        //! Use the configured input kind as given, or derive it from the extension of the file when it was not set
+        self.input_kind.unwrap_or_else(|| InputKind::from_file_name(file_name))
+    }
+
+    pub fn get_unroll_bound(&self) -> Result<u32, WombatError> {
+        //! A loop header is always entered once, so a bound of 0 is an error rather than being raised to 1
+        if self.unroll_bound == 0 {
+            return Err(WombatError::InvalidUnrollBound);
+        }
+        Ok(self.unroll_bound)
+    }
+
+    pub fn get_replay(&self, input_kind: InputKind) -> Result<bool, WombatError> {
+        //! Replaying needs a Rust source, so requesting it for a bitcode input is an error rather than being ignored
+        match (self.replay, input_kind) {
+            (Some(true), InputKind::Bytecode) => Err(WombatError::ReplayWithoutSource),
+            (Some(replay), _) => Ok(replay),
+            (None, input_kind) => Ok(input_kind != InputKind::Bytecode),
+        }
+    }
+}
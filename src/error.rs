@@ -0,0 +1,82 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum WombatError {
+    /// The input file does not exist
+    InvalidFile(String),
+    /// rustc rejected the source file, with its error output
+    CompilationFailed(String),
+    /// The input file is not a valid LLVM module, with the reason given by LLVM
+    InvalidModule(String),
+    /// No function of the module matches the requested name
+    FunctionNotFound(String),
+    /// No basic block of the function matches the name of a node in its control flow graph
+    BasicBlockNotFound(String),
+    /// A call passes a different number of arguments than the called function has parameters
+    ArgumentCountMismatch {
+        function_name: String,
+        parameter_count: usize,
+        argument_count: u32,
+    },
+    /// A constant operand that cannot be parsed
+    UnsupportedConstant(String),
+    /// An integer type without a corresponding Rust type
+    UnsupportedIntegerType(String),
+    /// A value whose type cannot be modelled where the instruction requires it, with the type
+    UnsupportedType(String),
+    /// An instruction outside of any basic block or without an operand its opcode requires, with the instruction
+    MalformedInstruction(String),
+    /// A call whose callee is not a named function, such as a call through a function pointer, with the call
+    UnsupportedCall(String),
+    /// An unroll bound of 0, which would leave not even the first iteration of a loop to analyse
+    InvalidUnrollBound,
+    /// Replay was requested for a bitcode or IR input, which has no Rust source to call the function from
+    ReplayWithoutSource,
+    Io(io::Error),
+    /// The report could not be serialized to JSON
+    Serialization(serde_json::Error),
+}
+
+impl fmt::Display for WombatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WombatError::InvalidFile(file_name) => write!(f, "{:?} is an invalid file. Please provide a valid file.", file_name),
+            WombatError::CompilationFailed(stderr) => write!(f, "Failed to generate bytecode file:\n{}", stderr),
+            WombatError::InvalidModule(reason) => write!(f, "Not a valid LLVM module: {}", reason),
+            WombatError::FunctionNotFound(function_name) => write!(f, "Function {:?} not found in the module", function_name),
+            WombatError::BasicBlockNotFound(node) => write!(f, "Basic block {:?} not found in its function", node),
+            WombatError::ArgumentCountMismatch {
+                function_name,
+                parameter_count,
+                argument_count,
+            } => write!(f, "Call to {} passes {} arguments for {} parameters", function_name, argument_count, parameter_count),
+            WombatError::UnsupportedConstant(value) => write!(f, "Currently unsupported constant {:?}", value),
+            WombatError::UnsupportedIntegerType(size) => write!(f, "Currently unsupported integer type of size {:?}", size),
+            WombatError::UnsupportedType(value_type) => write!(f, "Currently unsupported type {:?}", value_type),
+            WombatError::MalformedInstruction(instruction) => write!(f, "Malformed instruction {:?}", instruction),
+            WombatError::UnsupportedCall(instruction) => write!(f, "Currently unsupported call {:?} of a value that does not name a function", instruction),
+            WombatError::InvalidUnrollBound => write!(f, "The unroll bound must be at least 1 to analyse the first iteration of a loop"),
+            WombatError::ReplayWithoutSource => write!(f, "Counterexamples cannot be replayed without a Rust source file"),
+            WombatError::Io(error) => write!(f, "{}", error),
+            WombatError::Serialization(error) => write!(f, "Failed to serialize the report: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for WombatError {}
+
+impl From<io::Error> for WombatError {
+    fn from(error: io::Error) -> Self {
+        WombatError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for WombatError {
+    fn from(error: serde_json::Error) -> Self {
+        WombatError::Serialization(error)
+    }
+}
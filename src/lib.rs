@@ -1,4 +1,21 @@
 pub mod codegen;
+pub mod config;
 pub mod control_flow_graph;
+pub mod error;
+pub mod report;
 pub mod symbolic_execution;
 pub mod utils;
+
+/// Attach an invariant to the enclosing loop, which must hold every time the marker is reached
+///
+/// Loops containing an invariant are verified for any number of iterations instead of being unrolled.
+#[macro_export]
+macro_rules! invariant {
+    ($condition:expr) => {
+        $crate::invariant($condition)
+    };
+}
+
+/// Marker recognised by the symbolic execution, prefer the `invariant!` macro
+#[inline(never)]
+pub fn invariant(_condition: bool) {}
@@ -1,12 +1,19 @@
 // Copyright (c) 2023 Benjamin Jialong Wu
 // This code is licensed under MIT license (see LICENSE.md for details)
 
+use std::process;
+
 use clap::Parser;
 
 use tracing_core::Level;
 use tracing_subscriber::FmtSubscriber;
 
+use wombat_symx::codegen::codegen_call::DEFAULT_RECURSION_BOUND;
+use wombat_symx::config::{AnalysisConfig, InputKind};
+use wombat_symx::report::OutputFormat;
 use wombat_symx::symbolic_execution::symbolic_execution;
+use wombat_symx::utils::unroll_loops::DEFAULT_UNROLL_BOUND;
+use wombat_symx::utils::var_utils::IntegerEncoding;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -15,7 +22,7 @@ struct Args {
     #[clap(short, long)]
     debug: bool,
 
-    /// Set file name to perform symbolic execution on
+    /// Set file name to perform symbolic execution on, either a Rust source file or LLVM bitcode (.bc) or IR (.ll)
     #[clap()]
     file_name: String,
 
@@ -26,6 +33,46 @@ struct Args {
     /// Enable benchmark mode which disables compilation of test files
     #[clap(short, long)]
     benchmark_mode: bool,
+
+    /// Set how integers are modelled: "int" (unbounded with range constraints) or "bv" (bit-precise bitvectors)
+    #[clap(short, long, default_value = "int")]
+    encoding: IntegerEncoding,
+
+    /// Set the maximum number of times a loop header is entered before the execution is cut off as bound exhausted, at least 1
+    #[clap(short, long, default_value_t = DEFAULT_UNROLL_BOUND)]
+    unroll: u32,
+
+    /// Set the maximum number of nested recursive calls inlined before the execution is cut off as bound exhausted
+    #[clap(short, long, default_value_t = DEFAULT_RECURSION_BOUND)]
+    recursion_bound: u32,
+
+    /// Enable function summaries which encode each callee once and reuse the encoding at every call site
+    #[clap(short, long)]
+    summaries: bool,
+
+    /// Enable checking every panic site separately, reporting each reachable site with a counterexample
+    #[clap(short, long)]
+    all_panics: bool,
+
+    /// Set the maximum number of distinct counterexamples printed for each reachable panic
+    #[clap(short, long, default_value_t = 1)]
+    counterexamples: u32,
+
+    /// Enable minimising the absolute value of each integer argument of the counterexamples, in order of the parameters
+    #[clap(short, long)]
+    minimize: bool,
+
+    /// Disable calling the function with each counterexample to capture the actual error
+    #[clap(long)]
+    no_replay: bool,
+
+    /// Set the number of milliseconds the solver may spend on each check before the verdict is unknown
+    #[clap(short, long)]
+    timeout: Option<u64>,
+
+    /// Set how the report is printed: "text" (human readable), "json" (a single JSON object for tooling) or "sarif" (SARIF 2.1.0 for code scanning)
+    #[clap(short, long, default_value = "text")]
+    format: OutputFormat,
 }
 
 fn main() {
@@ -40,7 +87,31 @@ fn main() {
     // _guard resets the current default dispatcher to the prior default when dropped
     let _guard = tracing::subscriber::set_default(subscriber);
 
+    let mut config = AnalysisConfig::new()
+        .solver_timeout_ms(features.timeout)
+        .encoding(features.encoding)
+        .unroll_bound(features.unroll)
+        .recursion_bound(features.recursion_bound)
+        .use_summaries(features.summaries)
+        .enumerate_panics(features.all_panics)
+        .counterexample_count(features.counterexamples)
+        .minimize(features.minimize)
+        .format(features.format);
+    // The input kind and replay are otherwise derived from the extension of the file
+    if features.benchmark_mode {
+        config = config.input_kind(InputKind::PrecompiledSource);
+    }
+    if features.no_replay {
+        config = config.replay(false);
+    }
+
     let file_name = String::from(&features.file_name);
     let function_name = String::from(&features.function_name);
-    symbolic_execution(&file_name, &function_name, features.benchmark_mode);
+    // Scripts and CI can tell the verdict apart without parsing the report
+    let exit_code = match symbolic_execution(&file_name, &function_name, &config) {
+        Some(true) => 0,
+        Some(false) => 1,
+        None => 2,
+    };
+    process::exit(exit_code);
 }
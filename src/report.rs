@@ -0,0 +1,304 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::WombatError;
+use crate::utils::panic_utils::{PanicSite, PANIC_KINDS};
+
+/// Incremented whenever a field of the JSON report is renamed, removed or changes meaning
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// Rule of the counterexamples whose panic site could not be recovered from the model
+pub const SARIF_UNKNOWN_PANIC_RULE_ID: &str = "panic";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable report
+    Text,
+    /// Single JSON object following the schema of `Report`
+    Json,
+    /// SARIF 2.1.0 log with a result for every counterexample
+    Sarif,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            _ => Err(format!("Unknown output format {:?}, expected \"text\", \"json\" or \"sarif\"", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Safe,
+    Unsafe,
+    /// No panic within the bounds, but some execution was cut off by a bound
+    BoundExhausted,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            Verdict::Safe => "safe",
+            Verdict::Unsafe => "unsafe",
+            Verdict::BoundExhausted => "unknown (bound exhausted)",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ArgumentReport {
+    pub name: String,
+    /// Rust type of the parameter, or its LLVM type if the source does not declare it
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Rust literal of the value
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Counterexample {
+    pub arguments: Vec<ArgumentReport>,
+    /// Panic site reached by the arguments, if it could be recovered from the model
+    pub panic: Option<PanicSite>,
+    /// Offending values of an index out of bounds or division panic
+    pub detail: Option<String>,
+    /// Standard error of the function called with the arguments
+    pub replay_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicSiteStatus {
+    Reachable,
+    Unreachable,
+    /// No execution within the bounds reaches the site, but some execution was cut off by a bound
+    NotReached,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PanicSiteReport {
+    pub panic: PanicSite,
+    pub status: PanicSiteStatus,
+    pub counterexamples: Vec<Counterexample>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Timing {
+    /// Compilation of the source file to bytecode
+    pub compile_ms: u64,
+    /// Loading, transforming and encoding the module
+    pub encode_ms: u64,
+    /// Solving, including the search for counterexamples and their replay
+    pub solve_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    pub file_name: String,
+    /// Demangled path of the analysed function
+    pub function_name: String,
+    pub verdict: Verdict,
+    pub counterexamples: Vec<Counterexample>,
+    /// Every panic site of the function, if they were checked separately
+    pub panic_sites: Option<Vec<PanicSiteReport>>,
+    pub timing: Timing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownReason {
+    /// No panic within the bounds, but some execution was cut off by a bound
+    BoundExhausted,
+    /// The solver could not decide whether a panic is reachable
+    SolverUnknown,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum AnalysisResult {
+    Safe,
+    /// The first counterexample found, if any was requested
+    Unsafe {
+        counterexample: Option<Counterexample>,
+    },
+    Unknown {
+        reason: UnknownReason,
+    },
+}
+
+impl From<&Report> for AnalysisResult {
+    fn from(report: &Report) -> Self {
+        match report.verdict {
+            Verdict::Safe => AnalysisResult::Safe,
+            Verdict::BoundExhausted => AnalysisResult::Unknown {
+                reason: UnknownReason::BoundExhausted,
+            },
+            Verdict::Unknown => AnalysisResult::Unknown { reason: UnknownReason::SolverUnknown },
+            Verdict::Unsafe => {
+                let panic_site_counterexamples = report.panic_sites.iter().flatten().flat_map(|panic_site| panic_site.counterexamples.iter());
+                AnalysisResult::Unsafe {
+                    counterexample: report.counterexamples.iter().chain(panic_site_counterexamples).next().cloned(),
+                }
+            }
+        }
+    }
+}
+
+fn print_counterexamples(counterexamples: &[Counterexample], function_name: &str) {
+    for (i, counterexample) in counterexamples.iter().enumerate() {
+        if counterexamples.len() > 1 {
+            println!("\n--- Counterexample {} ---", i + 1);
+        }
+        println!("\nUnsafe values:");
+        for argument in &counterexample.arguments {
+            println!("\t{:?} = {}", argument.name, argument.value);
+        }
+        if let Some(panic) = &counterexample.panic {
+            println!("\nReachable panic: {}", panic.kind.get_description());
+            if let Some(message) = &panic.message {
+                println!("\tMessage: {:?}", message);
+            }
+            if let Some(location) = &panic.location {
+                println!("\tLocation: {}:{}:{}", location.file, location.line, location.column);
+            }
+        }
+        if let Some(detail) = &counterexample.detail {
+            println!("\n{}", detail);
+        }
+        if let Some(replay_error) = &counterexample.replay_error {
+            println!("\nError from calling function {} with unsafe arguments:", function_name);
+            println!("\t{}", replay_error.replace('\n', "\n\t"));
+        }
+    }
+}
+
+fn get_sarif_result(counterexample: &Counterexample, rules: &[Value], file_name: &str, function_name: &str) -> Value {
+    //! Result for the panic reached by the counterexample, located at the panic site if known and at the analysed file otherwise
+    let arguments: Vec<String> = counterexample.arguments.iter().map(|argument| format!("{} = {}", argument.name, argument.value)).collect();
+    let description = counterexample.panic.as_ref().map_or("panic", |panic| panic.kind.get_description());
+    let mut message = format!("Reachable {} in {} with arguments ({})", description, function_name, arguments.join(", "));
+    if let Some(panic_message) = counterexample.panic.as_ref().and_then(|panic| panic.message.as_ref()) {
+        message.push_str(&format!(": {:?}", panic_message));
+    }
+    if let Some(detail) = &counterexample.detail {
+        message.push_str(&format!(". {}", detail));
+    }
+
+    let physical_location = match counterexample.panic.as_ref().and_then(|panic| panic.location.as_ref()) {
+        Some(location) => json!({
+            "artifactLocation": { "uri": location.file },
+            "region": { "startLine": location.line, "startColumn": location.column }
+        }),
+        None => json!({ "artifactLocation": { "uri": file_name } }),
+    };
+    // Panics whose site could not be recovered from the model fall under the generic rule
+    let rule_id = counterexample.panic.as_ref().map_or(SARIF_UNKNOWN_PANIC_RULE_ID, |panic| panic.kind.get_id());
+    let mut result = json!({
+        "ruleId": rule_id,
+        "level": "error",
+        "message": { "text": message },
+        "locations": [{ "physicalLocation": physical_location }],
+        "properties": { "arguments": counterexample.arguments }
+    });
+    if let Some(rule_index) = rules.iter().position(|rule| rule["id"] == rule_id) {
+        result["ruleIndex"] = json!(rule_index);
+    }
+    result
+}
+
+impl Report {
+    pub fn print(&self, format: OutputFormat) -> Result<(), WombatError> {
+        match format {
+            OutputFormat::Text => self.print_text(),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            OutputFormat::Sarif => println!("{}", serde_json::to_string_pretty(&self.get_sarif())?),
+        }
+        Ok(())
+    }
+
+    pub fn get_sarif(&self) -> Value {
+        //! SARIF 2.1.0 log with a rule per panic kind and a result per counterexample
+        let mut rules: Vec<Value> = PANIC_KINDS
+            .iter()
+            .map(|kind| {
+                json!({
+                    "id": kind.get_id(),
+                    "shortDescription": { "text": format!("Reachable panic: {}", kind.get_description()) }
+                })
+            })
+            .collect();
+        rules.push(json!({
+            "id": SARIF_UNKNOWN_PANIC_RULE_ID,
+            "shortDescription": { "text": "Reachable panic of an unknown kind" }
+        }));
+        let panic_site_counterexamples = self.panic_sites.iter().flatten().flat_map(|panic_site| panic_site.counterexamples.iter());
+        let results: Vec<Value> = self
+            .counterexamples
+            .iter()
+            .chain(panic_site_counterexamples)
+            .map(|counterexample| get_sarif_result(counterexample, &rules, &self.file_name, &self.function_name))
+            .collect();
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules
+                    }
+                },
+                "results": results,
+                "properties": { "functionName": self.function_name, "verdict": self.verdict }
+            }]
+        })
+    }
+
+    fn print_text(&self) {
+        println!("\nFunction safety: {}", self.verdict.get_description());
+        print_counterexamples(&self.counterexamples, &self.function_name);
+        let panic_sites = match &self.panic_sites {
+            Some(panic_sites) => panic_sites,
+            None => return,
+        };
+
+        let (reachable_sites, unreachable_sites): (Vec<&PanicSiteReport>, Vec<&PanicSiteReport>) = panic_sites.iter().partition(|panic_site| panic_site.status == PanicSiteStatus::Reachable);
+        for panic_site in &reachable_sites {
+            println!("\n--- Panic site: {} ---", panic_site.panic.get_summary());
+            print_counterexamples(&panic_site.counterexamples, &self.function_name);
+        }
+
+        println!("\nReachable panic sites: {}", reachable_sites.len());
+        for panic_site in &reachable_sites {
+            println!("\t{}", panic_site.panic.get_summary());
+        }
+        // Panic sites are only proven unreachable if no execution was cut off by a bound
+        let is_bound_exhausted = unreachable_sites.iter().any(|panic_site| panic_site.status == PanicSiteStatus::NotReached);
+        println!(
+            "\n{} panic sites: {}",
+            if is_bound_exhausted { "Not reached within the bounds" } else { "Unreachable" },
+            unreachable_sites.len()
+        );
+        for panic_site in &unreachable_sites {
+            println!("\t{}", panic_site.panic.get_summary());
+        }
+    }
+}
@@ -4,6 +4,9 @@
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::time::Instant;
+
+use rustc_demangle::demangle;
 
 use tracing::{debug, error, warn};
 
@@ -12,19 +15,44 @@ use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::Module as InkwellModule;
 use inkwell::passes::{PassManager, PassManagerBuilder};
 
-use z3::ast::{Ast, Bool, Int};
+use inkwell::types::{AnyType, BasicTypeEnum};
+use inkwell::values::{FunctionValue, InstructionOpcode};
+
+use z3::ast::{Ast, Bool, Dynamic, Int, BV};
 use z3::Context as Z3Context;
-use z3::{Config, SatResult, Solver};
+use z3::{Config, Model, Optimize, SatResult, Solver};
 
+use crate::codegen::codegen_basic_block::is_panic_block;
+use crate::codegen::codegen_call::is_general_call;
 use crate::codegen::codegen_function::codegen_function;
+use crate::codegen::codegen_memory::{get_block_memory_name, get_memory_cell_value, get_memory_var, get_new_region_base};
+use crate::codegen::codegen_summary::SummaryCache;
+use crate::config::{AnalysisConfig, InputKind};
+use crate::error::WombatError;
+use crate::report::{AnalysisResult, ArgumentReport, Counterexample, PanicSiteReport, PanicSiteStatus, Report, Timing, Verdict, REPORT_SCHEMA_VERSION};
+use crate::utils::debug_info_utils::{DebugInfo, DebugType, DebugTypeKind};
 use crate::utils::function_utils::{get_all_function_argument_names, get_function_by_name, get_function_name};
+use crate::utils::model_utils::get_argument_values;
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::panic_utils::get_panic_site;
 use crate::utils::pretty_print::print_file_functions;
 use crate::utils::resolve_phi_to_dsa::resolve_phi_to_dsa;
-use crate::utils::var_utils::{get_min_max_signed_int, get_var_name};
+use crate::utils::unroll_loops::{is_bound_exhausted_block, is_loop_cut_block, unroll_loops};
+use crate::utils::var_utils::{get_called_function, get_int_from_i128, get_int_width, get_min_max_signed_int, get_typed_var, get_var_name, is_slice_pointer_type, IntegerEncoding};
 
 pub const MAIN_FUNCTION_NAMESPACE: &str = "";
 pub const COMMON_END_NODE: &str = "common_end_node";
 pub const PANIC_VAR_NAME: &str = "is_panic";
+pub const PANIC_SITE_VAR_NAME: &str = "panic_site";
+pub const BOUND_EXHAUSTED_VAR_NAME: &str = "is_bound_exhausted";
+pub const BOUNDS_CHECK_FUNCTION_NAME: &str = "core::panicking::panic_bounds_check";
+pub const BOUNDS_CHECK_PANIC_VAR_NAME: &str = "is_bounds_check_panic";
+pub const BOUNDS_CHECK_INDEX_VAR_NAME: &str = "bounds_check_index";
+pub const BOUNDS_CHECK_LENGTH_VAR_NAME: &str = "bounds_check_length";
+pub const DIVISION_BY_ZERO_PANIC_VAR_NAME: &str = "is_division_by_zero_panic";
+pub const DIVISION_OVERFLOW_PANIC_VAR_NAME: &str = "is_division_overflow_panic";
+pub const DIVISION_DIVIDEND_VAR_NAME: &str = "division_dividend";
+pub const DIVISION_DIVISOR_VAR_NAME: &str = "division_divisor";
 pub const MAIN_FUNCTION_RETURN_REGISTER: &str = "wombat_symx_return_register";
 
 struct FileDropper<'a> {
@@ -33,30 +61,29 @@ struct FileDropper<'a> {
 
 impl Drop for FileDropper<'_> {
     fn drop(&mut self) {
-        fs::remove_file(self.file_name).expect("Failed to delete file.");
+        if let Err(error) = fs::remove_file(self.file_name) {
+            warn!("Failed to delete file {:?}: {}", self.file_name, error);
+        }
     }
 }
 
-fn get_inkwell_module<'a>(context: &'a InkwellContext, file_name: &String) -> Option<InkwellModule<'a>> {
+fn is_llvm_ir_file(file_name: &str) -> bool {
+    Path::new(file_name).extension().map_or(false, |extension| extension == "ll")
+}
+
+fn get_inkwell_module<'a>(context: &'a InkwellContext, file_name: &String) -> Result<InkwellModule<'a>, WombatError> {
     let path = Path::new(&file_name);
     if !path.is_file() {
-        error!("{:?} is an invalid file. Please provide a valid file.", file_name);
-        return None;
+        return Err(WombatError::InvalidFile(file_name.clone()));
     }
 
-    let buffer = MemoryBuffer::create_from_file(path).unwrap();
-    let module_result = InkwellModule::parse_bitcode_from_buffer(&buffer, context);
-
-    // Check the module is from a valid bytecode file
-    if module_result.is_err() {
-        error!(
-            "{:?} is not a valid LLVM bitcode file. Please pass in a valid bc file.\nThe module_result is below:\n{:?}",
-            file_name, module_result
-        );
-        return None;
+    let buffer = MemoryBuffer::create_from_file(path).map_err(|error| WombatError::InvalidModule(error.to_string()))?;
+    if is_llvm_ir_file(file_name) {
+        // Textual IR, such as from --emit=llvm-ir or a hand-written reproducer
+        return context.create_module_from_ir(buffer).map_err(|error| WombatError::InvalidModule(error.to_string()));
     }
-    let module = module_result.unwrap();
-    Some(module)
+    // Check the module is from a valid bytecode file
+    InkwellModule::parse_bitcode_from_buffer(&buffer, context).map_err(|error| WombatError::InvalidModule(error.to_string()))
 }
 
 pub fn get_module_name_from_file_name(file_name: &str) -> String {
@@ -65,6 +92,8 @@ pub fn get_module_name_from_file_name(file_name: &str) -> String {
         start_index = last_slash_index + 1;
     }
     let end_index = file_name.rfind('.').unwrap_or(file_name.len());
+    // Cargo appends a hash to the file names of crates (e.g. `my_crate-0123abcd.bc`), whereas crate names never contain a hyphen
+    let end_index = file_name[start_index..end_index].find('-').map_or(end_index, |hyphen_index| start_index + hyphen_index);
     file_name[start_index..end_index].to_string()
 }
 
@@ -81,176 +110,657 @@ fn convert_to_ssa(module: &InkwellModule) {
     }
 }
 
-pub fn symbolic_execution(file_name: &String, function_name: &String, is_benchmark_mode: bool) -> Option<bool> {
+fn get_model_int_string(model: &Model, var: &Int) -> String {
+    //! Print an integer of the model as a Rust literal rather than as an SMT-LIB term such as `(- 1)`
+    model.eval(var, true).unwrap().to_string().replace(['(', ')', ' '], "")
+}
+
+fn get_wrapped_discriminant<'a>(solver: &'a Solver<'_>, discriminant: i128, tag_width: u32, encoding: IntegerEncoding) -> Dynamic<'a> {
+    //! Tags are held as signed integers of their width in the Int encoding and as bitvectors otherwise
+    let modulus = 1_i128 << tag_width.min(126);
+    let mut discriminant = discriminant.rem_euclid(modulus);
+    if discriminant >= modulus / 2 {
+        discriminant -= modulus;
+    }
+    match encoding {
+        IntegerEncoding::Int => Dynamic::from_ast(&get_int_from_i128(solver, discriminant)),
+        IntegerEncoding::BitVector => Dynamic::from_ast(&BV::from_int(&get_int_from_i128(solver, discriminant), tag_width)),
+    }
+}
+
+fn get_parameter_type<'a>(var_name: &str, parameter_types: &'a [(String, DebugType)]) -> Option<&'a DebugType> {
+    //! Find the debug type of the parameter held by an input variable, which for scalar pairs is held by its first input
+    let parameter_name = var_name[MAIN_FUNCTION_NAMESPACE.len()..].replace('%', "");
+    let parameter_name = parameter_name.strip_suffix(".0").unwrap_or(&parameter_name);
+    parameter_types.iter().find(|(name, _)| name.eq(parameter_name)).map(|(_, parameter_type)| parameter_type)
+}
+
+fn constrain_enum_tags(
+    context: &InkwellContext,
+    function: &FunctionValue,
+    solver: &Solver,
+    parameter_types: &[(String, DebugType)],
+    entry_memory_name: &str,
+    encoding: IntegerEncoding,
+) -> Result<(), WombatError> {
+    //! Enum parameters only hold the tags of their variants, either passed directly or stored in the pointee on entry
+    let memory_var = get_memory_var(solver, entry_memory_name);
+    for input in function.get_params() {
+        let var_name = get_var_name(&input, solver, MAIN_FUNCTION_NAMESPACE, encoding)?;
+        if var_name.ends_with(".1") {
+            continue;
+        }
+        let parameter_type = match get_parameter_type(&var_name, parameter_types) {
+            Some(parameter_type) => parameter_type,
+            None => continue,
+        };
+
+        // Enums are passed by reference, as a pointer to a copy when they are large, or as their tag and the fields of their variant
+        let input_type = input.get_type().as_any_type_enum();
+        let (debug_enum, is_stored) = match (&parameter_type.kind, input_type.is_pointer_type()) {
+            (DebugTypeKind::Pointer(pointee_type), true) => match &pointee_type.kind {
+                DebugTypeKind::Enum(debug_enum) => (debug_enum, true),
+                _ => continue,
+            },
+            (DebugTypeKind::Enum(debug_enum), is_pointer) => (debug_enum, is_pointer),
+            _ => continue,
+        };
+        // The tag of a niche-optimised enum is a field of one of its variants, which may hold any other value
+        let (tag_offset, tag_size) = match debug_enum.tag {
+            Some((tag_offset, tag_size)) if tag_size > 0 && !debug_enum.is_niche() => (tag_offset, tag_size),
+            _ => continue,
+        };
+        let (tag, tag_type) = if is_stored {
+            let tag_type = context.custom_width_int_type((tag_size * 8) as u32).as_any_type_enum();
+            let tag_address = Int::add(
+                solver.get_context(),
+                &[&Int::new_const(solver.get_context(), var_name.as_str()), &Int::from_u64(solver.get_context(), tag_offset)],
+            );
+            (get_memory_cell_value(solver, &memory_var.select(&tag_address).as_int().unwrap(), &tag_type, encoding), tag_type)
+        } else if tag_offset == 0 {
+            (get_typed_var(solver, &var_name, &input_type, encoding), input_type)
+        } else {
+            continue;
+        };
+        let tag = match tag {
+            Some(tag) if tag_type.is_int_type() && get_int_width(&tag_type) > 1 => tag,
+            _ => continue,
+        };
+
+        let is_variant_tags: Vec<Bool> = debug_enum
+            .variants
+            .iter()
+            .filter_map(|variant| variant.discriminant)
+            .map(|discriminant| tag._eq(&get_wrapped_discriminant(solver, discriminant, get_int_width(&tag_type), encoding)))
+            .collect();
+        let is_variant_tag_refs: Vec<&Bool> = is_variant_tags.iter().collect();
+        solver.assert(&Bool::or(solver.get_context(), &is_variant_tag_refs));
+    }
+    Ok(())
+}
+
+fn get_argument_type_name(parameter_name: &str, func_arg_names: &[(String, String, BasicTypeEnum)], parameter_types: &[(String, DebugType)]) -> String {
+    //! Prefer the Rust type of the parameter, falling back to its LLVM type when the debug info does not name it
+    if let Some((_, parameter_type)) = parameter_types.iter().find(|(name, parameter_type)| name.eq(parameter_name) && !parameter_type.name.is_empty()) {
+        return parameter_type.name.clone();
+    }
+    func_arg_names
+        .iter()
+        .find(|(arg_name, _, _)| arg_name[MAIN_FUNCTION_NAMESPACE.len()..].replace('%', "").trim_end_matches(".0").eq(parameter_name))
+        .map_or_else(String::new, |(_, _, var_type)| var_type.print_to_string().to_string())
+}
+
+fn replay_counterexample(file_name: &String, function_name: &String, argument_values: &[(String, String)]) -> Result<String, WombatError> {
+    //! Call the function with the pathological input and capture the actual error
+    let mut source_file_content = fs::read_to_string(file_name)?;
+    if !function_name.eq(&String::from("main")) {
+        // Inject custom main function as entry point for test program to generate stack trace
+        source_file_content = source_file_content.replace("fn main", "fn _main");
+        source_file_content = format!("{}\nfn main() {{{}(", source_file_content, function_name);
+        for (_, argument_value) in argument_values {
+            source_file_content = format!("{}{},", source_file_content, argument_value);
+        }
+        source_file_content = format!("{});}}", source_file_content);
+    }
+    debug!("{}", source_file_content);
+
+    let mut temp_file_path_base_end_index = 0;
+    if file_name.rfind('/').is_some() {
+        temp_file_path_base_end_index = file_name.rfind('/').unwrap() + 1;
+    }
+    let temp_source_file_name = format!(
+        "{}temp_wombat_symx_{}",
+        &file_name[0..temp_file_path_base_end_index],
+        &file_name[temp_file_path_base_end_index..file_name.len()]
+    );
+    fs::write(&temp_source_file_name, source_file_content)?;
+
+    let _temp_source_file_dropper = FileDropper { file_name: &temp_source_file_name };
+
+    let temp_executable_file_name = &temp_source_file_name[0..temp_source_file_name.rfind('.').unwrap()];
+
+    let output = Command::new("rustc").args([&temp_source_file_name, "-o", temp_executable_file_name]).output()?;
+    if !output.status.success() {
+        return Err(WombatError::CompilationFailed(String::from_utf8_lossy(&output.stderr).to_string()));
+    }
+
+    let _temp_executable_file_dropper = FileDropper {
+        file_name: &String::from(temp_executable_file_name),
+    };
+
+    let output = Command::new(format!("./{}", temp_executable_file_name)).output()?;
+    Ok(String::from_utf8_lossy(&output.stderr).to_string())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_counterexample(
+    module: &AnalysedModule,
+    solver: &Solver,
+    model: &Model,
+    file_name: &String,
+    function_name: &String,
+    func_arg_names: &[(String, String, BasicTypeEnum)],
+    parameter_types: &[(String, DebugType)],
+    entry_memory_name: &str,
+    encoding: IntegerEncoding,
+    replay: bool,
+) -> Counterexample {
+    //! Extract the pathological input of the model and the panic it reaches, then replay the input to capture the actual error
+    // Supports int types, booleans, floats, references, arrays, slices, structs, tuples and enums
+
+    // Difficult to manually extract variables in model. Instead, parse and clean model output
+    let model_str = format!("{:?}", model);
+    let mut model_output_tokens: Vec<String> = model_str.split('\n').map(|s| s.to_string()).collect();
+    model_output_tokens.sort();
+    let cleaned_model_output = model_output_tokens.join("\n");
+    debug!("{}", cleaned_model_output);
+
+    let argument_values = get_argument_values(module, solver, model, func_arg_names, parameter_types, entry_memory_name, encoding);
+    let arguments = argument_values
+        .iter()
+        .map(|(parameter_name, value_string)| ArgumentReport {
+            name: parameter_name.clone(),
+            type_name: get_argument_type_name(parameter_name, func_arg_names, parameter_types),
+            value: value_string.clone(),
+        })
+        .collect();
+
+    let panic_site_id = model.eval(&Int::new_const(solver.get_context(), PANIC_SITE_VAR_NAME), true).unwrap().as_u64();
+    let panic = panic_site_id
+        .and_then(|panic_site_id| module.get_panic_site_block(panic_site_id))
+        .map(|panic_block| get_panic_site(module, &panic_block));
+
+    let mut detail = None;
+    let bounds_check_panic_var = Bool::new_const(solver.get_context(), BOUNDS_CHECK_PANIC_VAR_NAME);
+    if model.eval(&bounds_check_panic_var, true).unwrap().as_bool().unwrap() {
+        let index = get_model_int_string(model, &Int::new_const(solver.get_context(), BOUNDS_CHECK_INDEX_VAR_NAME));
+        let length = get_model_int_string(model, &Int::new_const(solver.get_context(), BOUNDS_CHECK_LENGTH_VAR_NAME));
+        detail = Some(format!("Index out of bounds: the len is {} but the index is {}", length, index));
+    }
+
+    let divisor = get_model_int_string(model, &Int::new_const(solver.get_context(), DIVISION_DIVISOR_VAR_NAME));
+    let division_by_zero_panic_var = Bool::new_const(solver.get_context(), DIVISION_BY_ZERO_PANIC_VAR_NAME);
+    if model.eval(&division_by_zero_panic_var, true).unwrap().as_bool().unwrap() {
+        detail = Some(format!("Division by zero: the divisor is {}", divisor));
+    }
+    let division_overflow_panic_var = Bool::new_const(solver.get_context(), DIVISION_OVERFLOW_PANIC_VAR_NAME);
+    if model.eval(&division_overflow_panic_var, true).unwrap().as_bool().unwrap() {
+        let dividend = get_model_int_string(model, &Int::new_const(solver.get_context(), DIVISION_DIVIDEND_VAR_NAME));
+        detail = Some(format!("Division overflow: the dividend is {} and the divisor is {}", dividend, divisor));
+    }
+
+    // A failed replay leaves the counterexample itself intact
+    let replay_error = if !replay {
+        None
+    } else {
+        match replay_counterexample(file_name, function_name, &argument_values) {
+            Ok(replay_error) => Some(replay_error),
+            Err(error) => {
+                warn!("Unable to replay the counterexample: {}", error);
+                None
+            }
+        }
+    };
+    Counterexample {
+        arguments,
+        panic,
+        detail,
+        replay_error,
+    }
+}
+
+fn get_blocking_clause<'a>(solver: &'a Solver<'_>, model: &Model, func_arg_names: &[(String, String, BasicTypeEnum)], entry_memory_name: &str, encoding: IntegerEncoding) -> Bool<'a> {
+    //! Bool that excludes the argument values of the model, where references are distinguished by the value they point to on entry
+    let memory_var = get_memory_var(solver, entry_memory_name);
+    let mut differences = Vec::<Bool>::new();
+    for (_, z3_name, var_type) in func_arg_names {
+        let var_type = var_type.as_any_type_enum();
+        let var = if var_type.is_pointer_type() {
+            Dynamic::from_ast(&memory_var.select(&Int::new_const(solver.get_context(), z3_name.as_str())))
+        } else {
+            match get_typed_var(solver, z3_name, &var_type, encoding) {
+                Some(var) => var,
+                None => continue,
+            }
+        };
+        let value = model.eval(&var, true).unwrap();
+        differences.push(var._eq(&value).not());
+    }
+    Bool::or(solver.get_context(), &differences.iter().collect::<Vec<&Bool>>())
+}
+
+fn get_minimal_model<'a>(solver: &'a Solver<'_>, func_arg_names: &[(String, String, BasicTypeEnum)], entry_memory_name: &str, encoding: IntegerEncoding) -> Option<Model<'a>> {
+    //! Minimise the absolute value of each integer argument (and of each integer a reference argument points to) in order of the parameters
+    let optimize = Optimize::new(solver.get_context());
+    for assertion in solver.get_assertions() {
+        optimize.assert(&assertion);
+    }
+    let memory_var = get_memory_var(solver, entry_memory_name);
+    let const_0 = Int::from_i64(solver.get_context(), 0);
+    for (_, z3_name, var_type) in func_arg_names {
+        let var_type = var_type.as_any_type_enum();
+        let value = if var_type.is_pointer_type() && var_type.into_pointer_type().get_element_type().is_int_type() {
+            memory_var.select(&Int::new_const(solver.get_context(), z3_name.as_str())).as_int()
+        } else if var_type.is_int_type() && get_int_width(&var_type) > 1 {
+            match encoding {
+                IntegerEncoding::Int => Some(Int::new_const(solver.get_context(), z3_name.as_str())),
+                IntegerEncoding::BitVector => Some(BV::new_const(solver.get_context(), z3_name.as_str(), get_int_width(&var_type)).to_int(true)),
+            }
+        } else {
+            None
+        };
+        if let Some(value) = value {
+            // Objectives are prioritised lexicographically in the order they are added
+            optimize.minimize(&value.lt(&const_0).ite(&value.unary_minus(), &value));
+        }
+    }
+    if optimize.check(&[]) != SatResult::Sat {
+        warn!("Unable to minimise the counterexample, an arbitrary counterexample is printed instead");
+        return None;
+    }
+    optimize.get_model()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_counterexamples(
+    module: &AnalysedModule,
+    solver: &Solver,
+    config: &AnalysisConfig,
+    replay: bool,
+    file_name: &String,
+    function_name: &String,
+    func_arg_names: &[(String, String, BasicTypeEnum)],
+    parameter_types: &[(String, DebugType)],
+    entry_memory_name: &str,
+) -> Vec<Counterexample> {
+    //! Find up to the given number of counterexamples, each with argument values distinct from the previous ones
+    let mut counterexamples = Vec::<Counterexample>::new();
+    solver.push();
+    let encoding = config.encoding;
+    for i in 0..config.counterexample_count {
+        if i > 0 && solver.check() != SatResult::Sat {
+            debug!("No further distinct counterexamples");
+            break;
+        }
+        let model = if config.minimize {
+            get_minimal_model(solver, func_arg_names, entry_memory_name, encoding).unwrap_or_else(|| solver.get_model().unwrap())
+        } else {
+            solver.get_model().unwrap()
+        };
+        counterexamples.push(get_counterexample(
+            module,
+            solver,
+            &model,
+            file_name,
+            function_name,
+            func_arg_names,
+            parameter_types,
+            entry_memory_name,
+            encoding,
+            replay,
+        ));
+        solver.assert(&get_blocking_clause(solver, &model, func_arg_names, entry_memory_name, encoding));
+    }
+    solver.pop(1);
+    counterexamples
+}
+
+fn get_reachable_functions<'a>(module: &'a InkwellModule, function: &FunctionValue<'a>) -> Vec<FunctionValue<'a>> {
+    //! The function along with every function inlined into it, directly or transitively
+    let mut reachable_functions = vec![*function];
+    let mut i = 0;
+    while i < reachable_functions.len() {
+        for bb in reachable_functions[i].get_basic_blocks() {
+            let mut next_instruction = bb.get_first_instruction();
+            while let Some(current_instruction) = next_instruction {
+                next_instruction = current_instruction.get_next_instruction();
+                if current_instruction.get_opcode() != InstructionOpcode::Call {
+                    continue;
+                }
+                // Calls through function pointers are rejected once the call is encoded
+                let call_operand = match get_called_function(module, &current_instruction) {
+                    Ok(call_operand) => call_operand,
+                    Err(_) => continue,
+                };
+                let called_function_name = get_function_name(&call_operand);
+                if !is_general_call(module, &called_function_name) {
+                    continue;
+                }
+                if let Some(called_function) = get_function_by_name(module, &called_function_name) {
+                    if !reachable_functions.contains(&called_function) {
+                        reachable_functions.push(called_function);
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+    reachable_functions
+}
+
+fn get_panic_site_ids(module: &AnalysedModule, function: &FunctionValue) -> Vec<u64> {
+    //! Identify the panicking blocks of the function and of the functions inlined into it
+    let mut panic_site_ids = Vec::<u64>::new();
+    for reachable_function in get_reachable_functions(module, function) {
+        for bb in reachable_function.get_basic_blocks() {
+            if is_panic_block(&bb) == Some(true) && !is_bound_exhausted_block(&bb) && !is_loop_cut_block(&bb) {
+                panic_site_ids.push(module.get_panic_site_id(&bb));
+            }
+        }
+    }
+    panic_site_ids
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_panic_site_reports(
+    module: &AnalysedModule,
+    function: &FunctionValue,
+    solver: &Solver,
+    is_confirmed_unsafe: bool,
+    is_bound_exhausted: bool,
+    config: &AnalysisConfig,
+    replay: bool,
+    file_name: &String,
+    function_name: &String,
+    func_arg_names: &[(String, String, BasicTypeEnum)],
+    parameter_types: &[(String, DebugType)],
+    entry_memory_name: &str,
+) -> Vec<PanicSiteReport> {
+    //! Check the reachability of every panic site separately, with counterexamples for each reachable one
+    // Panic sites are only proven unreachable if no execution was cut off by a bound
+    let unreached_status = if is_bound_exhausted { PanicSiteStatus::NotReached } else { PanicSiteStatus::Unreachable };
+    let mut panic_site_reports = Vec::<PanicSiteReport>::new();
+    for panic_site_id in get_panic_site_ids(module, function) {
+        let panic = get_panic_site(module, &module.get_panic_site_block(panic_site_id).unwrap());
+        // Without any reachable panic, the solver only holds the executions cut off by a bound
+        if !is_confirmed_unsafe {
+            panic_site_reports.push(PanicSiteReport {
+                panic,
+                status: unreached_status,
+                counterexamples: Vec::new(),
+            });
+            continue;
+        }
+        solver.push();
+        solver.assert(&Int::new_const(solver.get_context(), PANIC_SITE_VAR_NAME)._eq(&Int::from_u64(solver.get_context(), panic_site_id)));
+        let panic_site_report = if solver.check() == SatResult::Sat {
+            PanicSiteReport {
+                panic,
+                status: PanicSiteStatus::Reachable,
+                counterexamples: get_counterexamples(module, solver, config, replay, file_name, function_name, func_arg_names, parameter_types, entry_memory_name),
+            }
+        } else {
+            PanicSiteReport {
+                panic,
+                status: unreached_status,
+                counterexamples: Vec::new(),
+            }
+        };
+        panic_site_reports.push(panic_site_report);
+        solver.pop(1);
+    }
+    panic_site_reports
+}
+
+pub fn get_report(file_name: &String, function_name: &String, config: &AnalysisConfig) -> Result<Report, WombatError> {
+    //! Analyse the function and collect the verdict along with its counterexamples, without printing anything
+    let start_time = Instant::now();
+    let encoding = config.encoding;
     let context = InkwellContext::create();
 
-    let bytecode_file_name = format!("{}.bc", &file_name[0..file_name.rfind('.').unwrap_or(file_name.len())]);
+    // Bitcode and IR files are analysed directly, without a Rust source to compile, read types from or replay counterexamples with
+    let input_kind = config.get_input_kind(file_name);
+    let replay = config.get_replay(input_kind)?;
+    let unroll_bound = config.get_unroll_bound()?;
+    let is_bytecode_input = input_kind == InputKind::Bytecode;
+    let is_benchmark_mode = input_kind == InputKind::PrecompiledSource || is_bytecode_input;
+    let bytecode_file_name = if is_bytecode_input {
+        file_name.clone()
+    } else {
+        format!("{}.bc", &file_name[0..file_name.rfind('.').unwrap_or(file_name.len())])
+    };
 
     if !is_benchmark_mode {
         // Benchmark mode skips compilation and assumes user has already compiled bytecode & executable
-        Command::new("rustc")
-            .args(["--emit=llvm-bc", &file_name, "-o", &bytecode_file_name])
-            .status()
-            .expect("Failed to generate bytecode file!");
+        let output = Command::new("rustc").args(["--emit=llvm-bc", "-g", file_name, "-o", &bytecode_file_name]).output()?;
+        if !output.status.success() {
+            return Err(WombatError::CompilationFailed(String::from_utf8_lossy(&output.stderr).to_string()));
+        }
     }
 
     let _temp_bc_file_dropper = if !is_benchmark_mode { Some(FileDropper { file_name: &bytecode_file_name }) } else { None };
+    let compile_time = start_time.elapsed();
 
-    let module_result = get_inkwell_module(&context, &bytecode_file_name);
-    module_result.as_ref()?;
-
-    let module = module_result.unwrap();
+    let module = get_inkwell_module(&context, &bytecode_file_name)?;
+    // Parameter names and types are read from the debug info before the module is transformed
+    let debug_info = DebugInfo::new(&module);
     let module_name = get_module_name_from_file_name(&bytecode_file_name);
     let target_function_name_prefix = format!("{}::{}", module_name, function_name);
 
     // Initialize the Z3 and Builder objects
-    let cfg = Config::new();
+    let mut cfg = Config::new();
+    if let Some(solver_timeout_ms) = config.solver_timeout_ms {
+        // Checks that time out are unknown, which leaves the verdict unknown
+        cfg.set_timeout_msec(solver_timeout_ms);
+    }
     let ctx = Z3Context::new(&cfg);
     let solver = Solver::new(&ctx);
 
     // Save function argument names before removing store/alloca instructions
-    let all_func_arg_names = get_all_function_argument_names(&module, &solver, MAIN_FUNCTION_NAMESPACE);
+    let all_func_arg_names = get_all_function_argument_names(&module, &solver, MAIN_FUNCTION_NAMESPACE, encoding)?;
 
     // Convert to dynamic single assignment form (DSA)
 
     print_file_functions(&module);
 
+    unroll_loops(&context, &module, unroll_bound);
     convert_to_ssa(&module);
     resolve_phi_to_dsa(&context, &module);
+    // Panic site ids and the other facts about the module are derived once its transformations are done
+    let module = AnalysedModule::new(&module);
 
-    let function_option = get_function_by_name(&module, &target_function_name_prefix);
-    function_option?;
-    let function = function_option.unwrap();
+    let function = get_function_by_name(&module, &target_function_name_prefix)
+        // Functions of hand-written IR or of other languages are usually not mangled
+        .or_else(|| module.get_function(function_name))
+        // Functions of a crate named differently from the file are given by their full path
+        .or_else(|| if function_name.contains("::") { get_function_by_name(&module, function_name) } else { None })
+        .ok_or_else(|| WombatError::FunctionNotFound(target_function_name_prefix.clone()))?;
 
-    let func_arg_names_option = all_func_arg_names.get(&get_function_name(&function.as_global_value().as_pointer_value()));
-    func_arg_names_option?;
-    let func_arg_names = func_arg_names_option.unwrap();
+    let func_arg_names = all_func_arg_names
+        .get(&get_function_name(&function.as_global_value().as_pointer_value()))
+        .ok_or_else(|| WombatError::FunctionNotFound(target_function_name_prefix.clone()))?;
+
+    // Function summaries are computed on the first call to each function and reused for later calls
+    let summaries = SummaryCache::default();
 
     let call_stack = function.get_name().to_str().unwrap();
-    codegen_function(&module, &function, &solver, MAIN_FUNCTION_NAMESPACE, call_stack, COMMON_END_NODE, MAIN_FUNCTION_RETURN_REGISTER);
+    codegen_function(
+        &module,
+        &function,
+        &solver,
+        MAIN_FUNCTION_NAMESPACE,
+        call_stack,
+        COMMON_END_NODE,
+        MAIN_FUNCTION_RETURN_REGISTER,
+        encoding,
+        config.recursion_bound,
+        if config.use_summaries { Some(&summaries) } else { None },
+    )?;
+
+    // Unsigned parameters, struct fields and enum variants are only distinguishable in the debug info
+    let parameter_types = debug_info.get_parameter_types(&function);
+    let target_data = module.get_target_data();
 
     // Constrain int inputs
     // Supports signed int types and booleans
-    for input in function.get_params() {
-        if input.get_type().to_string().eq("\"i1\"") {
+    // Bitvectors are bounded by their width so only the Int encoding needs range constraints
+    let params = function.get_params();
+    for (i, input) in params.iter().enumerate() {
+        if i > 0 && is_slice_pointer_type(&params[i - 1].get_type().as_any_type_enum()) && input.get_type().is_int_type() {
+            // Slice lengths are at most isize::MAX
+            let length_name = get_var_name(input, &solver, MAIN_FUNCTION_NAMESPACE, encoding)?;
+            let is_valid_length = match encoding {
+                IntegerEncoding::Int => Int::new_const(solver.get_context(), length_name).ge(&Int::from_i64(solver.get_context(), 0)),
+                IntegerEncoding::BitVector => {
+                    let width = get_int_width(&input.get_type().as_any_type_enum());
+                    BV::new_const(solver.get_context(), length_name, width).bvsge(&BV::from_i64(solver.get_context(), 0, width))
+                }
+            };
+            solver.assert(&is_valid_length);
+        }
+
+        if input.get_type().to_string().eq("\"i1\"") || (input.get_type().is_int_type() && encoding == IntegerEncoding::BitVector) {
             continue;
         } else if input.get_type().is_int_type() {
-            let arg = Int::new_const(solver.get_context(), get_var_name(&input, &solver, MAIN_FUNCTION_NAMESPACE));
-            let (min_int_val, max_int_val) = get_min_max_signed_int(&input.get_type().to_string().as_str().replace('\"', "")[1..]);
-            let min_int = Int::from_i64(solver.get_context(), min_int_val);
-            let max_int = Int::from_i64(solver.get_context(), max_int_val);
+            let arg = Int::new_const(solver.get_context(), get_var_name(input, &solver, MAIN_FUNCTION_NAMESPACE, encoding)?);
+            let (min_int_val, max_int_val) = get_min_max_signed_int(get_int_width(&input.get_type().as_any_type_enum()))?;
+            let min_int = get_int_from_i128(&solver, min_int_val);
+            let max_int = get_int_from_i128(&solver, max_int_val);
             solver.assert(&Bool::and(solver.get_context(), &[&arg.ge(&min_int), &arg.le(&max_int)]));
+        } else if input.get_type().is_float_type() {
+            // Every float is a valid input, including NaN and the infinities
+            continue;
+        } else if input.get_type().is_pointer_type() {
+            // Input references point to their own region of memory
+            let var_name = get_var_name(input, &solver, MAIN_FUNCTION_NAMESPACE, encoding)?;
+            let arg = Int::new_const(solver.get_context(), var_name.as_str());
+            let is_region_base = arg._eq(&get_new_region_base(&module, &solver));
+            // Niche-optimised enums of at most a pointer, such as an `Option<&T>`, are passed as the pointer and hold null in their untagged variants
+            let is_niche_pointer = get_parameter_type(&var_name, &parameter_types).map_or(false, |parameter_type| match &parameter_type.kind {
+                DebugTypeKind::Enum(debug_enum) => debug_enum.is_niche() && parameter_type.size <= target_data.get_abi_size(&input.get_type().as_any_type_enum()),
+                _ => false,
+            });
+            if is_niche_pointer {
+                solver.assert(&Bool::or(solver.get_context(), &[&is_region_base, &arg._eq(&Int::from_u64(solver.get_context(), 0))]));
+            } else {
+                solver.assert(&is_region_base);
+            }
         } else {
             warn!("Currently unsupported type {:?} for input parameter to {}", input.get_type().to_string(), function_name);
         }
     }
 
+    // Declarations have no body to analyse
+    let start_node = function.get_first_basic_block().ok_or_else(|| WombatError::FunctionNotFound(target_function_name_prefix.clone()))?;
+    let start_node_var_name = format!("{}{}", MAIN_FUNCTION_NAMESPACE, start_node.get_name().to_str().unwrap());
+    let entry_memory_name = get_block_memory_name(&start_node_var_name, 0);
+    constrain_enum_tags(&context, &function, &solver, &parameter_types, &entry_memory_name, encoding)?;
+
     let common_end_node_var = Bool::new_const(solver.get_context(), String::from(COMMON_END_NODE));
     let panic_var = Bool::new_const(solver.get_context(), String::from(PANIC_VAR_NAME));
     solver.assert(&common_end_node_var._eq(&panic_var.not()));
 
-    let start_node = function.get_first_basic_block().unwrap();
-    let start_node_var_name = format!("{}{}", MAIN_FUNCTION_NAMESPACE, start_node.get_name().to_str().unwrap());
-    let start_node_var = Bool::new_const(solver.get_context(), start_node_var_name);
+    let start_node_var = Bool::new_const(solver.get_context(), start_node_var_name.as_str());
     solver.assert(&start_node_var.not());
 
     debug!("{}", format!("\nSolver:\n{:?}", solver));
+    let encode_time = start_time.elapsed() - compile_time;
 
     // Attempt resolving the model (and obtaining the respective arg values if panic found)
+    // Executions cut off by loop unrolling are first treated as non-panicking so a counterexample is always a real panic
+    let bound_exhausted_var = Bool::new_const(solver.get_context(), String::from(BOUND_EXHAUSTED_VAR_NAME));
+    solver.push();
+    solver.assert(&bound_exhausted_var.not());
     let satisfiability = solver.check();
 
-    let is_confirmed_safe = satisfiability == SatResult::Unsat;
+    let mut is_bound_exhausted = false;
+    if satisfiability == SatResult::Unsat {
+        // No panic within the unroll bound, so check whether the bound cut off any execution
+        solver.pop(1);
+        solver.assert(&bound_exhausted_var);
+        is_bound_exhausted = solver.check() == SatResult::Sat;
+    }
+
+    let is_confirmed_safe = satisfiability == SatResult::Unsat && !is_bound_exhausted;
     let is_confirmed_unsafe = satisfiability == SatResult::Sat;
-    println!(
-        "\nFunction safety: {}",
-        if is_confirmed_safe {
-            "safe"
-        } else if is_confirmed_unsafe {
-            "unsafe"
-        } else {
-            "unknown"
-        }
-    );
+    let verdict = if is_confirmed_safe {
+        Verdict::Safe
+    } else if is_confirmed_unsafe {
+        Verdict::Unsafe
+    } else if is_bound_exhausted {
+        Verdict::BoundExhausted
+    } else {
+        Verdict::Unknown
+    };
+
+    let mut counterexamples = Vec::<Counterexample>::new();
+    let mut panic_sites = None;
+    if is_confirmed_unsafe && !config.enumerate_panics {
+        // Exhibit pathological inputs if the function is unsafe
+        counterexamples = get_counterexamples(&module, &solver, config, replay, file_name, function_name, func_arg_names, &parameter_types, &entry_memory_name);
+    } else if config.enumerate_panics {
+        panic_sites = Some(get_panic_site_reports(
+            &module,
+            &function,
+            &solver,
+            is_confirmed_unsafe,
+            is_bound_exhausted,
+            config,
+            replay,
+            file_name,
+            function_name,
+            func_arg_names,
+            &parameter_types,
+            &entry_memory_name,
+        ));
+    }
 
-    if is_confirmed_unsafe {
-        // Exhibit a pathological input if the function is unsafe
-        // Supports signed int types and booleans
-        let model = solver.get_model().unwrap();
-
-        // Difficult to manually extract variables in model. Instead, parse and clean model output
-        let model_str = format!("{:?}", model);
-        let mut model_output_tokens: Vec<String> = model_str.split('\n').map(|s| s.to_string()).collect();
-        model_output_tokens.sort();
-        let cleaned_model_output = model_output_tokens.join("\n");
-        debug!("{}", cleaned_model_output);
-
-        println!("\nUnsafe values:");
-        let mut argument_values = Vec::<String>::new();
-        for (arg_name, z3_name, var_type) in func_arg_names {
-            let arg_name_without_namespace = &arg_name[MAIN_FUNCTION_NAMESPACE.len()..];
-            let arg_name_without_namespace_and_percent = arg_name_without_namespace.replace('%', "");
-            let value_string;
-            if var_type.to_string().eq("\"i1\"") {
-                let value = Bool::new_const(solver.get_context(), z3_name.as_str());
-                value_string = format!("{:?}", model.eval(&value, true).unwrap());
-                let cleaned_value_string = &value_string.replace('(', "").replace(')', "").replace(' ', "");
-                println!("\t{:?} = {}", &arg_name_without_namespace_and_percent, cleaned_value_string);
-                argument_values.push(cleaned_value_string.to_string());
-            } else if var_type.is_int_type() {
-                let value = Int::new_const(solver.get_context(), z3_name.as_str());
-                value_string = format!("{:?}", model.eval(&value, true).unwrap());
-                let cleaned_value_string = &value_string.replace('(', "").replace(')', "").replace(' ', "");
-                println!("\t{:?} = {}", &arg_name_without_namespace_and_percent, cleaned_value_string);
-                argument_values.push(cleaned_value_string.to_string());
-            } else {
-                warn!("{} is not a supported parameter type!", var_type);
-            }
-        }
+    let total_time = start_time.elapsed();
+    let report = Report {
+        schema_version: REPORT_SCHEMA_VERSION,
+        file_name: file_name.clone(),
+        function_name: format!("{:#}", demangle(function.get_name().to_str().unwrap())),
+        verdict,
+        counterexamples,
+        panic_sites,
+        timing: Timing {
+            compile_ms: compile_time.as_millis() as u64,
+            encode_ms: encode_time.as_millis() as u64,
+            solve_ms: (total_time - compile_time - encode_time).as_millis() as u64,
+            total_ms: total_time.as_millis() as u64,
+        },
+    };
+    Ok(report)
+}
 
-        let mut source_file_content = fs::read_to_string(file_name).unwrap();
-        if !function_name.eq(&String::from("main")) {
-            // Inject custom main function as entry point for test program to generate stack trace
-            source_file_content = source_file_content.replace("fn main", "fn _main");
-            source_file_content = format!("{}\nfn main() {{{}(", source_file_content, function_name);
-            for argument_value in &argument_values {
-                source_file_content = format!("{}{},", source_file_content, argument_value);
-            }
-            source_file_content = format!("{});}}", source_file_content);
-        }
-        debug!("{}", source_file_content);
+pub fn analyze(file_name: &String, function_name: &String, config: &AnalysisConfig) -> Result<AnalysisResult, WombatError> {
+    //! Analyse the function for a reachable panic, with the first counterexample found if it is unsafe
+    let report = get_report(file_name, function_name, config)?;
+    Ok(AnalysisResult::from(&report))
+}
 
-        let mut temp_file_path_base_end_index = 0;
-        if file_name.rfind('/').is_some() {
-            temp_file_path_base_end_index = file_name.rfind('/').unwrap() + 1;
+pub fn symbolic_execution(file_name: &String, function_name: &String, config: &AnalysisConfig) -> Option<bool> {
+    //! Print the report of the function, returning whether it is safe or None if the analysis is inconclusive or fails
+    let report_result = get_report(file_name, function_name, config).and_then(|report| report.print(config.format).map(|_| report));
+    match report_result {
+        Ok(report) => match report.verdict {
+            Verdict::Safe => Some(true),
+            Verdict::Unsafe => Some(false),
+            Verdict::BoundExhausted | Verdict::Unknown => None,
+        },
+        Err(error) => {
+            error!("{}", error);
+            None
         }
-        let temp_source_file_name = format!(
-            "{}temp_wombat_symx_{}",
-            &file_name[0..temp_file_path_base_end_index],
-            &file_name[temp_file_path_base_end_index..file_name.len()]
-        );
-        fs::write(&temp_source_file_name, &source_file_content).expect("Failed to write file!");
-
-        let _temp_source_file_dropper = FileDropper { file_name: &temp_source_file_name };
-
-        let temp_executable_file_name = &temp_source_file_name[0..temp_source_file_name.rfind('.').unwrap()];
-
-        Command::new("rustc")
-            .args([&temp_source_file_name, "-o", temp_executable_file_name])
-            .status()
-            .expect("Failed to generate executable file!");
-
-        let _temp_executable_file_dropper = FileDropper {
-            file_name: &String::from(temp_executable_file_name),
-        };
-
-        println!("\nError from calling function {} with unsafe arguments:", function_name);
-        println!(
-            "\t{}",
-            std::str::from_utf8(&Command::new(format!("./{}", temp_executable_file_name)).output().ok().unwrap().stderr)
-                .unwrap()
-                .replace('\n', "\n\t")
-        );
     }
-
-    Some(is_confirmed_safe)
 }
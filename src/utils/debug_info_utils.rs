@@ -0,0 +1,435 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::collections::HashMap;
+
+use inkwell::module::Module as InkwellModule;
+use inkwell::values::FunctionValue;
+
+// Deepest nesting of types read from the debug info, which guards against recursive types
+const MAX_DEBUG_TYPE_DEPTH: u32 = 16;
+
+// Prefixes of the names rustc gives to references and raw pointers
+const POINTER_NAME_PREFIXES: [&str; 4] = ["&mut ", "&", "*mut ", "*const "];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AggregateKind {
+    Struct,
+    TupleStruct,
+    Tuple,
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugField {
+    /// Field name, or index for tuples and tuple structs
+    pub name: String,
+    /// Byte offset from the start of the enclosing struct, or of the enclosing enum for the fields of a variant
+    pub offset: u64,
+    pub field_type: DebugType,
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugVariant {
+    pub name: String,
+    /// Unit variants are structs without fields
+    pub kind: AggregateKind,
+    pub fields: Vec<DebugField>,
+    /// Value of the tag for the variant, which is absent for the variant whose field holds the tag of a niche-optimised enum
+    pub discriminant: Option<i128>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugEnum {
+    /// Path printed before variant names, empty for the variants of `Option` and `Result` in the prelude
+    pub prefix: String,
+    /// Byte offset and size of the tag, which is absent for enums of a single variant
+    pub tag: Option<(u64, u64)>,
+    pub variants: Vec<DebugVariant>,
+}
+
+impl DebugEnum {
+    pub fn get_variant(&self, tag: i128, tag_width: u32) -> Option<&DebugVariant> {
+        //! Find the variant held by a tag of the given width (tags carry no sign), which is the untagged variant if no discriminant matches
+        let modulus = 1_i128 << tag_width.min(126);
+        self.variants
+            .iter()
+            .find(|variant| variant.discriminant.map(|discriminant| discriminant.rem_euclid(modulus)) == Some(tag.rem_euclid(modulus)))
+            .or_else(|| self.variants.iter().find(|variant| variant.discriminant.is_none()))
+    }
+
+    pub fn is_niche(&self) -> bool {
+        //! Niche-optimised enums, such as `Option<&T>`, keep their tag in an invalid value of a field of their untagged variant
+        self.variants.iter().any(|variant| variant.discriminant.is_none())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DebugTypeKind {
+    Int {
+        is_unsigned: bool,
+    },
+    Bool,
+    Char,
+    Float,
+    /// References, raw pointers and boxes, whose pointee is only known for parameters
+    Pointer(Box<DebugType>),
+    /// References to slices, which are passed as a pointer to their elements followed by their length
+    Slice(Box<DebugType>),
+    Array(Box<DebugType>, u64),
+    Aggregate(AggregateKind, Vec<DebugField>),
+    Enum(DebugEnum),
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugType {
+    /// Name of the type as written in Rust
+    pub name: String,
+    /// Size in bytes, including padding
+    pub size: u64,
+    pub kind: DebugTypeKind,
+}
+
+impl DebugType {
+    pub fn unknown() -> Self {
+        DebugType {
+            name: String::new(),
+            size: 0,
+            kind: DebugTypeKind::Unknown,
+        }
+    }
+}
+
+pub fn get_literal_name(type_name: &str) -> &str {
+    //! Name of a struct or enum without its generic arguments, as used in its literals
+    &type_name[..type_name.find('<').unwrap_or(type_name.len())]
+}
+
+fn get_aggregate_kind(type_name: &str, fields: &[DebugField]) -> AggregateKind {
+    //! rustc names the fields of tuples and tuple structs `__0`, `__1` and so on
+    if type_name.starts_with('(') {
+        AggregateKind::Tuple
+    } else if !fields.is_empty() && fields.iter().all(|field| field.name.parse::<u64>().is_ok()) {
+        AggregateKind::TupleStruct
+    } else {
+        AggregateKind::Struct
+    }
+}
+
+struct MetadataNode {
+    /// Name of the specialized node such as `DIBasicType`, empty for tuples such as `!{!1, !2}`
+    kind: String,
+    fields: HashMap<String, String>,
+    elements: Vec<String>,
+}
+
+pub struct DebugInfo {
+    /// Metadata nodes of the module by their id such as `!12`
+    nodes: HashMap<String, MetadataNode>,
+    /// Subprogram of each function defined in the module by its LLVM name
+    subprograms: HashMap<String, String>,
+}
+
+fn split_operands(text: &str) -> Vec<&str> {
+    //! Split the operands of a metadata node on commas outside of strings and nested nodes
+    let mut operands = Vec::<&str>::new();
+    let mut depth = 0;
+    let mut is_in_string = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            // LLVM escapes quotes inside strings as \22
+            '"' => is_in_string = !is_in_string,
+            '(' | '{' if !is_in_string => depth += 1,
+            ')' | '}' if !is_in_string => depth -= 1,
+            ',' if !is_in_string && depth == 0 => {
+                operands.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !text[start..].trim().is_empty() {
+        operands.push(text[start..].trim());
+    }
+    operands
+}
+
+fn parse_metadata_node(text: &str) -> Option<MetadataNode> {
+    //! Parse a node printed as `!DIBasicType(name: "i32", size: 32)` or as a tuple `!{!1, !2}`
+    let text = text.trim();
+    let text = text.strip_prefix("distinct ").unwrap_or(text);
+    if let Some(elements) = text.strip_prefix("!{").and_then(|elements| elements.strip_suffix('}')) {
+        return Some(MetadataNode {
+            kind: String::new(),
+            fields: HashMap::new(),
+            elements: split_operands(elements).into_iter().map(String::from).collect(),
+        });
+    }
+    let text = text.strip_prefix('!')?;
+    let body_start = text.find('(')?;
+    let body = text[body_start + 1..].strip_suffix(')')?;
+    let fields = split_operands(body)
+        .into_iter()
+        .filter_map(|field| field.split_once(": ").map(|(key, value)| (key.trim().to_string(), value.trim().to_string())))
+        .collect();
+    Some(MetadataNode {
+        kind: text[..body_start].to_string(),
+        fields,
+        elements: Vec::new(),
+    })
+}
+
+fn get_defined_function_name(definition: &str) -> Option<String> {
+    //! Name of the function defined by a `define` line, which LLVM quotes if it contains special characters
+    let name = &definition[definition.find('@')? + 1..];
+    if let Some(quoted_name) = name.strip_prefix('"') {
+        Some(quoted_name[..quoted_name.find('"')?].to_string())
+    } else {
+        Some(name[..name.find('(')?].to_string())
+    }
+}
+
+fn decode_string(value: &str) -> String {
+    //! LLVM prints strings in quotes with special characters escaped as two hexadecimal digits, such as \22 for a quote
+    let value = value.trim().trim_matches('"');
+    let mut bytes = Vec::<u8>::new();
+    let mut i = 0;
+    while i < value.len() {
+        let escaped_byte = value
+            .get(i + 1..i + 3)
+            .filter(|_| value.as_bytes()[i] == b'\\')
+            .and_then(|hex_digits| u8::from_str_radix(hex_digits, 16).ok());
+        if let Some(escaped_byte) = escaped_byte {
+            bytes.push(escaped_byte);
+            i += 3;
+        } else {
+            bytes.push(value.as_bytes()[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+impl DebugInfo {
+    pub fn new(module: &InkwellModule) -> Self {
+        //! Parse the debug metadata of the module from its textual IR, which is empty for modules compiled without debug info
+        let mut debug_info = DebugInfo {
+            nodes: HashMap::new(),
+            subprograms: HashMap::new(),
+        };
+        for line in module.print_to_string().to_string().lines() {
+            if let Some(definition) = line.strip_prefix("define ") {
+                // Attachments of a function are printed between its parameters and its body
+                let attachments = &definition[definition.rfind(')').unwrap_or(0)..];
+                let subprogram = attachments.split_once("!dbg ").and_then(|(_, subprogram)| subprogram.split_whitespace().next());
+                if let (Some(function_name), Some(subprogram)) = (get_defined_function_name(definition), subprogram) {
+                    debug_info.subprograms.insert(function_name, subprogram.to_string());
+                }
+            } else if let Some((id, node)) = line.split_once(" = ") {
+                let is_numbered = id.len() > 1 && id.starts_with('!') && id[1..].chars().all(|c| c.is_ascii_digit());
+                if let (true, Some(node)) = (is_numbered, parse_metadata_node(node)) {
+                    debug_info.nodes.insert(id.to_string(), node);
+                }
+            }
+        }
+        debug_info
+    }
+
+    fn get_field(&self, id: &str, key: &str) -> Option<&str> {
+        self.nodes.get(id)?.fields.get(key).map(|value| value.as_str())
+    }
+
+    fn get_int_field(&self, id: &str, key: &str) -> Option<i128> {
+        //! Integer fields are printed as a number, optionally preceded by its LLVM type as in `extraData: i64 1`
+        self.get_field(id, key)?.split_whitespace().last()?.parse::<i128>().ok()
+    }
+
+    fn get_string_field(&self, id: &str, key: &str) -> Option<String> {
+        self.get_field(id, key).map(decode_string)
+    }
+
+    fn get_elements(&self, id: &str) -> Vec<&str> {
+        //! Operands of the tuple referenced by the `elements` field of a node
+        self.get_field(id, "elements")
+            .and_then(|elements| self.nodes.get(elements))
+            .map_or_else(Vec::new, |tuple| tuple.elements.iter().map(|element| element.as_str()).collect())
+    }
+
+    pub fn get_parameter_types(&self, function: &FunctionValue) -> Vec<(String, DebugType)> {
+        //! Names and Rust types of the parameters of the function, empty if it was compiled without debug info
+        let subprogram = match self.subprograms.get(function.get_name().to_str().unwrap()) {
+            Some(subprogram) => subprogram,
+            None => return Vec::new(),
+        };
+        let mut parameters = Vec::<(i128, String, &str)>::new();
+        for (id, node) in &self.nodes {
+            if node.kind != "DILocalVariable" || self.get_field(id, "scope") != Some(subprogram.as_str()) {
+                continue;
+            }
+            if let (Some(position), Some(name), Some(type_id)) = (self.get_int_field(id, "arg"), self.get_string_field(id, "name"), self.get_field(id, "type")) {
+                parameters.push((position, name, type_id));
+            }
+        }
+        parameters.sort();
+        parameters.into_iter().map(|(_, name, type_id)| (name, self.get_debug_type(type_id, 0))).collect()
+    }
+
+    fn get_members(&self, id: &str, depth: u32) -> Vec<DebugField> {
+        //! Fields of a struct in declaration order, with their byte offsets
+        self.get_elements(id)
+            .into_iter()
+            .filter(|member| self.get_field(member, "tag") == Some("DW_TAG_member"))
+            .map(|member| {
+                let name = self.get_string_field(member, "name").unwrap_or_default();
+                DebugField {
+                    name: name.strip_prefix("__").filter(|index| index.parse::<u64>().is_ok()).unwrap_or(name.as_str()).to_string(),
+                    offset: self.get_int_field(member, "offset").unwrap_or(0) as u64 / 8,
+                    field_type: self
+                        .get_field(member, "baseType")
+                        .map_or_else(DebugType::unknown, |field_type| self.get_debug_type(field_type, depth + 1)),
+                }
+            })
+            .collect()
+    }
+
+    fn get_debug_enum(&self, name: &str, variant_part: &str, depth: u32) -> DebugEnum {
+        //! The variant part of an enum holds its tag as the discriminator and its variants as members, whose fields are laid out from the start of the enum
+        let prefix = match get_literal_name(name) {
+            "Option" | "Result" => String::new(),
+            enum_name => format!("{}::", enum_name),
+        };
+        let tag = self.get_field(variant_part, "discriminator").map(|discriminator| {
+            (
+                self.get_int_field(discriminator, "offset").unwrap_or(0) as u64 / 8,
+                self.get_int_field(discriminator, "size").unwrap_or(0) as u64 / 8,
+            )
+        });
+        let variants = self
+            .get_elements(variant_part)
+            .into_iter()
+            .map(|member| {
+                let fields = self.get_field(member, "baseType").map_or_else(Vec::new, |variant_type| self.get_members(variant_type, depth + 1));
+                DebugVariant {
+                    name: self.get_string_field(member, "name").unwrap_or_default(),
+                    kind: get_aggregate_kind("", &fields),
+                    fields,
+                    discriminant: self.get_int_field(member, "extraData"),
+                }
+            })
+            .collect();
+        DebugEnum { prefix, tag, variants }
+    }
+
+    pub fn get_debug_type(&self, id: &str, depth: u32) -> DebugType {
+        //! Type of a parameter with the layout rustc chose for it, where only the pointees of the parameter itself are resolved
+        let node = match self.nodes.get(id) {
+            Some(node) if depth < MAX_DEBUG_TYPE_DEPTH => node,
+            _ => return DebugType::unknown(),
+        };
+        let name = self.get_type_name(id, depth);
+        let size = self.get_int_field(id, "size").unwrap_or(0) as u64 / 8;
+        let tag = self.get_field(id, "tag").unwrap_or_default();
+        let kind = match (node.kind.as_str(), tag) {
+            ("DIBasicType", _) if name == "()" => DebugTypeKind::Aggregate(AggregateKind::Tuple, Vec::new()),
+            ("DIBasicType", _) => match self.get_field(id, "encoding").unwrap_or_default() {
+                "DW_ATE_signed" | "DW_ATE_signed_char" => DebugTypeKind::Int { is_unsigned: false },
+                "DW_ATE_unsigned" | "DW_ATE_unsigned_char" => DebugTypeKind::Int { is_unsigned: true },
+                "DW_ATE_boolean" => DebugTypeKind::Bool,
+                "DW_ATE_UTF" => DebugTypeKind::Char,
+                "DW_ATE_float" => DebugTypeKind::Float,
+                _ => DebugTypeKind::Unknown,
+            },
+            ("DIDerivedType", "DW_TAG_pointer_type") => {
+                // Nested pointees are not printed, which also ends the expansion of recursive types
+                let pointee = match self.get_field(id, "baseType") {
+                    Some(pointee) if depth == 0 => self.get_debug_type(pointee, depth + 1),
+                    _ => DebugType::unknown(),
+                };
+                DebugTypeKind::Pointer(Box::new(pointee))
+            }
+            ("DIDerivedType", _) => return self.get_field(id, "baseType").map_or_else(DebugType::unknown, |base_type| self.get_debug_type(base_type, depth)),
+            ("DICompositeType", "DW_TAG_array_type") => {
+                let length = self.get_elements(id).first().and_then(|subrange| self.get_int_field(subrange, "count")).unwrap_or(0);
+                let element_type = self
+                    .get_field(id, "baseType")
+                    .map_or_else(DebugType::unknown, |element_type| self.get_debug_type(element_type, depth + 1));
+                DebugTypeKind::Array(Box::new(element_type), length as u64)
+            }
+            ("DICompositeType", "DW_TAG_enumeration_type") => {
+                // Enums without fields are held in their tag alone
+                let variants = self
+                    .get_elements(id)
+                    .into_iter()
+                    .map(|enumerator| DebugVariant {
+                        name: self.get_string_field(enumerator, "name").unwrap_or_default(),
+                        kind: AggregateKind::Struct,
+                        fields: Vec::new(),
+                        discriminant: self.get_int_field(enumerator, "value"),
+                    })
+                    .collect();
+                DebugTypeKind::Enum(DebugEnum {
+                    prefix: format!("{}::", get_literal_name(&name)),
+                    tag: Some((0, size)),
+                    variants,
+                })
+            }
+            ("DICompositeType", "DW_TAG_structure_type") => {
+                let elements = self.get_elements(id);
+                let variant_part = elements.iter().find(|element| self.get_field(element, "tag") == Some("DW_TAG_variant_part"));
+                let data_pointer = elements.iter().find(|member| self.get_string_field(member, "name").as_deref() == Some("data_ptr"));
+                if let Some(variant_part) = variant_part {
+                    DebugTypeKind::Enum(self.get_debug_enum(&name, variant_part, depth))
+                } else if let (true, Some(pointer_type)) = (name.starts_with(['&', '*']), data_pointer.and_then(|member| self.get_field(member, "baseType"))) {
+                    let element_type = self
+                        .get_field(pointer_type, "baseType")
+                        .map_or_else(DebugType::unknown, |element_type| self.get_debug_type(element_type, depth + 1));
+                    DebugTypeKind::Slice(Box::new(element_type))
+                } else {
+                    let fields = self.get_members(id, depth);
+                    DebugTypeKind::Aggregate(get_aggregate_kind(&name, &fields), fields)
+                }
+            }
+            _ => DebugTypeKind::Unknown,
+        };
+        DebugType { name, size, kind }
+    }
+
+    fn get_type_name(&self, id: &str, depth: u32) -> String {
+        //! Name of a type as written in Rust, where rustc gives the full path of the types behind references
+        let node = match self.nodes.get(id) {
+            Some(node) if depth < MAX_DEBUG_TYPE_DEPTH => node,
+            _ => return String::new(),
+        };
+        let name = self.get_string_field(id, "name").unwrap_or_default();
+        let tag = self.get_field(id, "tag").unwrap_or_default();
+        let base_type_name = || self.get_field(id, "baseType").map_or_else(String::new, |base_type| self.get_type_name(base_type, depth + 1));
+        match (node.kind.as_str(), tag) {
+            ("DIDerivedType", "DW_TAG_pointer_type") => match POINTER_NAME_PREFIXES.iter().find(|prefix| name.starts_with(*prefix)) {
+                Some(prefix) => format!("{}{}", prefix, base_type_name()),
+                // Such as boxes and function pointers
+                None => name,
+            },
+            ("DIDerivedType", _) => base_type_name(),
+            ("DICompositeType", "DW_TAG_array_type") => {
+                let length = self.get_elements(id).first().and_then(|subrange| self.get_int_field(subrange, "count")).unwrap_or(0);
+                format!("[{}; {}]", base_type_name(), length)
+            }
+            ("DICompositeType", _) => {
+                // Slices are passed as a pointer to their elements followed by their length
+                let data_pointer = self.get_elements(id).into_iter().find(|member| self.get_string_field(member, "name").as_deref() == Some("data_ptr"));
+                let prefix = POINTER_NAME_PREFIXES.iter().find(|prefix| name.starts_with(*prefix) && name[prefix.len()..].starts_with('['));
+                match (prefix, data_pointer.and_then(|member| self.get_field(member, "baseType"))) {
+                    (Some(prefix), Some(pointer_type)) => {
+                        let element_type_name = self
+                            .get_field(pointer_type, "baseType")
+                            .map_or_else(String::new, |element_type| self.get_type_name(element_type, depth + 1));
+                        format!("{}[{}]", prefix, element_type_name)
+                    }
+                    _ => name,
+                }
+            }
+            _ => name,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2023 Benjamin Jialong Wu
 // This code is licensed under MIT license (see LICENSE.md for details)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use inkwell::types::BasicTypeEnum;
 // use tracing::{debug};
@@ -13,7 +13,8 @@ use inkwell::values::{AnyValue, FunctionValue, InstructionOpcode, PointerValue};
 
 use z3::Solver;
 
-use crate::utils::var_utils::get_var_name;
+use crate::error::WombatError;
+use crate::utils::var_utils::{get_called_function, get_operand_value, get_var_name, IntegerEncoding};
 
 trait Named {
     fn get_name(&self) -> String;
@@ -22,42 +23,41 @@ trait Named {
 impl Named for inkwell::values::BasicValueEnum<'_> {
     fn get_name(&self) -> String {
         if self.is_array_value() {
-            self.into_array_value().get_name().to_str().unwrap().to_string()
+            self.into_array_value().get_name().to_string_lossy().to_string()
         } else if self.is_int_value() {
-            self.into_int_value().get_name().to_str().unwrap().to_string()
+            self.into_int_value().get_name().to_string_lossy().to_string()
         } else if self.is_float_value() {
-            self.into_float_value().get_name().to_str().unwrap().to_string()
+            self.into_float_value().get_name().to_string_lossy().to_string()
         } else if self.is_pointer_value() {
-            self.into_pointer_value().get_name().to_str().unwrap().to_string()
+            self.into_pointer_value().get_name().to_string_lossy().to_string()
         } else if self.is_struct_value() {
-            self.into_struct_value().get_name().to_str().unwrap().to_string()
+            self.into_struct_value().get_name().to_string_lossy().to_string()
         } else {
-            self.into_vector_value().get_name().to_str().unwrap().to_string()
+            self.into_vector_value().get_name().to_string_lossy().to_string()
         }
     }
 }
 
 // Returns a map of source code function argument names to Z3 module variable names
-pub fn get_function_argument_names<'a>(function: FunctionValue<'a>, solver: &Solver, namespace: &str) -> Vec<(String, String, BasicTypeEnum<'a>)> {
+pub fn get_function_argument_names<'a>(function: FunctionValue<'a>, solver: &Solver, namespace: &str, encoding: IntegerEncoding) -> Result<Vec<(String, String, BasicTypeEnum<'a>)>, WombatError> {
     let mut arg_names = Vec::<(String, String, BasicTypeEnum)>::new();
     for param in &function.get_params() {
         // debug!("Func param instr: {:?}", param);
         if param.get_name().is_empty() {
             // Var name is empty, find in start basic block
-            let alias_name = &get_var_name(&param.as_any_value_enum(), solver, namespace);
+            let alias_name = &get_var_name(&param.as_any_value_enum(), solver, namespace, encoding)?;
 
-            let start_block_option = function.get_first_basic_block();
-            if start_block_option.is_none() {
-                return arg_names;
-            }
-            let start_block = start_block_option.unwrap();
+            let start_block = match function.get_first_basic_block() {
+                Some(start_block) => start_block,
+                None => return Ok(arg_names),
+            };
             let mut instr = start_block.get_first_instruction();
-            while instr.is_some() {
-                if instr.unwrap().get_opcode() == InstructionOpcode::Store && *alias_name == get_var_name(&instr.unwrap().as_any_value_enum(), solver, namespace) {
-                    let arg_name = get_var_name(&instr.unwrap().get_operand(1).unwrap().left().unwrap().as_any_value_enum(), solver, namespace);
+            while let Some(current_instr) = instr {
+                if current_instr.get_opcode() == InstructionOpcode::Store && *alias_name == get_var_name(&current_instr.as_any_value_enum(), solver, namespace, encoding)? {
+                    let arg_name = get_var_name(&get_operand_value(&current_instr, 1)?.as_any_value_enum(), solver, namespace, encoding)?;
                     arg_names.push((arg_name.to_string(), alias_name.to_string(), param.get_type()));
                 }
-                instr = instr.unwrap().get_next_instruction();
+                instr = current_instr.get_next_instruction();
             }
         } else {
             let arg_name_string = format!("{}{}{}", namespace, "%", &param.get_name());
@@ -67,24 +67,29 @@ pub fn get_function_argument_names<'a>(function: FunctionValue<'a>, solver: &Sol
     }
 
     // debug!("Function arg names: {:?}", arg_names);
-    arg_names
+    Ok(arg_names)
 }
 
-pub fn get_all_function_argument_names<'a>(module: &'a InkwellModule, solver: &'a Solver, namespace: &'a str) -> HashMap<String, Vec<(String, String, BasicTypeEnum<'a>)>> {
+pub fn get_all_function_argument_names<'a>(
+    module: &'a InkwellModule,
+    solver: &'a Solver,
+    namespace: &'a str,
+    encoding: IntegerEncoding,
+) -> Result<HashMap<String, Vec<(String, String, BasicTypeEnum<'a>)>>, WombatError> {
     let mut all_func_arg_names = HashMap::<String, Vec<(String, String, BasicTypeEnum)>>::new();
 
     let mut next_function = module.get_first_function();
     while let Some(current_function) = next_function {
         let current_full_function_name = get_function_name(&current_function.as_global_value().as_pointer_value());
-        let function_argument_names = get_function_argument_names(current_function, solver, namespace);
+        let function_argument_names = get_function_argument_names(current_function, solver, namespace, encoding)?;
         all_func_arg_names.insert(current_full_function_name, function_argument_names);
         next_function = current_function.get_next_function();
     }
-    all_func_arg_names
+    Ok(all_func_arg_names)
 }
 
 pub fn get_function_name(function: &PointerValue) -> String {
-    return demangle(function.get_name().to_str().unwrap()).to_string();
+    return demangle(&function.get_name().to_string_lossy()).to_string();
 }
 
 pub fn get_function_by_name<'a>(module: &'a InkwellModule, target_function_name_prefix: &String) -> Option<FunctionValue<'a>> {
@@ -98,3 +103,32 @@ pub fn get_function_by_name<'a>(module: &'a InkwellModule, target_function_name_
     }
     None
 }
+
+pub fn is_recursive_function(module: &InkwellModule, function: &FunctionValue) -> bool {
+    //! Check whether the function can (indirectly) call itself
+    let mut visited = HashSet::<FunctionValue>::new();
+    let mut worklist = vec![*function];
+    while let Some(current_function) = worklist.pop() {
+        for bb in current_function.get_basic_blocks() {
+            let mut next_instruction = bb.get_first_instruction();
+            while let Some(current_instruction) = next_instruction {
+                if current_instruction.get_opcode() == InstructionOpcode::Call {
+                    // Calls through function pointers are rejected once the call is encoded
+                    let callee = get_called_function(module, &current_instruction)
+                        .ok()
+                        .and_then(|call_operand| module.get_function(&call_operand.get_name().to_string_lossy()));
+                    if let Some(callee) = callee {
+                        if callee == *function {
+                            return true;
+                        }
+                        if visited.insert(callee) {
+                            worklist.push(callee);
+                        }
+                    }
+                }
+                next_instruction = current_instruction.get_next_instruction();
+            }
+        }
+    }
+    false
+}
@@ -1,4 +1,9 @@
+pub mod debug_info_utils;
 pub mod function_utils;
+pub mod model_utils;
+pub mod module_utils;
+pub mod panic_utils;
 pub mod pretty_print;
 pub mod resolve_phi_to_dsa;
+pub mod unroll_loops;
 pub mod var_utils;
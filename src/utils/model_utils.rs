@@ -0,0 +1,484 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use tracing::warn;
+
+use inkwell::context::ContextRef;
+use inkwell::targets::TargetData;
+use inkwell::types::{AnyType, AnyTypeEnum, BasicTypeEnum};
+
+use z3::ast::{Array, Ast, Dynamic, Int};
+use z3::{Model, Solver};
+
+use crate::codegen::codegen_memory::{get_memory_cell_value, get_memory_var};
+use crate::symbolic_execution::MAIN_FUNCTION_NAMESPACE;
+use crate::utils::debug_info_utils::{get_literal_name, AggregateKind, DebugEnum, DebugField, DebugType, DebugTypeKind};
+use crate::utils::module_utils::AnalysedModule;
+use crate::utils::var_utils::{get_float_format, get_int_from_u128, get_int_width, get_typed_var, get_unsigned_int, get_wrapped_signed_int, is_slice_pointer_type, IntegerEncoding};
+
+// Longest slice printed in a counterexample, longer slices are truncated
+const MAX_PRINTED_SLICE_LENGTH: u64 = 64;
+
+/// Where the scalars of a value are read from
+enum ValueLocation<'a, 'ctx> {
+    /// Stored in the memory from the address
+    Memory(Array<'a>, Int<'a>),
+    /// Passed as immediates, each at its byte offset in the value
+    Immediates(Vec<(u64, Dynamic<'a>, AnyTypeEnum<'ctx>)>),
+}
+
+fn get_float_bits_literal(bits: u64, float_format: (u32, u32)) -> String {
+    //! Print a float as the shortest Rust literal that parses back to the exact same value
+    let (type_name, value) = if float_format == (8, 24) {
+        ("f32", f64::from(f32::from_bits(bits as u32)))
+    } else {
+        ("f64", f64::from_bits(bits))
+    };
+    if value.is_nan() {
+        format!("{}::NAN", type_name)
+    } else if value == f64::INFINITY {
+        format!("{}::INFINITY", type_name)
+    } else if value == f64::NEG_INFINITY {
+        format!("{}::NEG_INFINITY", type_name)
+    } else if type_name.eq("f32") {
+        format!("{:?}", f32::from_bits(bits as u32))
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+fn get_float_literal(model_value: &str, float_format: (u32, u32)) -> String {
+    //! Z3 prints special floats as `(_ NaN 8 24)` or `(_ -zero 8 24)` and every other float as `(fp #b0 #x7f #b000..)`
+    let (exponent_width, significand_width) = float_format;
+    let sign_bit = 1_u64 << (exponent_width + significand_width - 1);
+    let exponent_bits = ((1_u64 << exponent_width) - 1) << (significand_width - 1);
+    if model_value.contains("NaN") {
+        return get_float_bits_literal(exponent_bits | 1, float_format);
+    } else if model_value.contains("+oo") {
+        return get_float_bits_literal(exponent_bits, float_format);
+    } else if model_value.contains("-oo") {
+        return get_float_bits_literal(sign_bit | exponent_bits, float_format);
+    } else if model_value.contains("-zero") {
+        return get_float_bits_literal(sign_bit, float_format);
+    } else if model_value.contains("+zero") {
+        return get_float_bits_literal(0, float_format);
+    }
+
+    let mut bit_string = String::new();
+    for token in model_value.replace(['(', ')'], " ").split_whitespace() {
+        if let Some(binary_digits) = token.strip_prefix("#b") {
+            bit_string.push_str(binary_digits);
+        } else if let Some(hex_digits) = token.strip_prefix("#x") {
+            for hex_digit in hex_digits.chars() {
+                bit_string.push_str(&format!("{:04b}", hex_digit.to_digit(16).unwrap_or(0)));
+            }
+        }
+    }
+    get_float_bits_literal(u64::from_str_radix(&bit_string, 2).unwrap_or(0), float_format)
+}
+
+fn get_model_value_string(solver: &Solver, model: &Model, value: &Dynamic, value_type: &AnyTypeEnum, is_unsigned: bool) -> String {
+    //! Print the value of an integer or boolean in the model
+    let value_string = if let Some(value) = value.as_bv() {
+        // Print bitvectors as decimals rather than hexadecimal
+        format!("{:?}", model.eval(&value.to_int(!is_unsigned), true).unwrap())
+    } else if let (Some(value), true) = (value.as_int(), is_unsigned) {
+        format!("{:?}", model.eval(&get_unsigned_int(solver, &value, get_int_width(value_type)), true).unwrap())
+    } else {
+        format!("{:?}", model.eval(value, true).unwrap())
+    };
+    value_string.replace(['(', ')', ' '], "")
+}
+
+fn get_scalar_value_string(solver: &Solver, model: &Model, value: &Dynamic, value_type: &AnyTypeEnum, scalar_type: &DebugType) -> String {
+    //! Print a scalar as a literal of its Rust type
+    if let Some(float_format) = get_float_format(value_type) {
+        if value.as_float().is_some() {
+            return get_float_literal(&format!("{:?}", model.eval(value, true).unwrap()), float_format);
+        }
+        // Memory cells and integer immediates hold the IEEE-754 bits of floats
+        let bits = value.as_int().and_then(|bits| model.eval(&bits, true)).and_then(|bits| bits.as_u64()).unwrap_or(0);
+        return get_float_bits_literal(bits, float_format);
+    }
+    let is_unsigned = matches!(scalar_type.kind, DebugTypeKind::Int { is_unsigned: true } | DebugTypeKind::Char | DebugTypeKind::Pointer(_));
+    let value_string = get_model_value_string(solver, model, value, value_type, is_unsigned);
+    match (&scalar_type.kind, value_string.parse::<i128>()) {
+        // Booleans are held as bytes in memory and aggregates
+        (DebugTypeKind::Bool, Ok(value)) => String::from(if value == 0 { "false" } else { "true" }),
+        (DebugTypeKind::Char, Ok(value)) => u32::try_from(value).ok().and_then(char::from_u32).map_or(value_string, |c| format!("{:?}", c)),
+        _ => value_string,
+    }
+}
+
+fn get_scalar_width(scalar_type: &AnyTypeEnum) -> u32 {
+    get_float_format(scalar_type).map_or_else(|| get_int_width(scalar_type), |(exponent_width, significand_width)| exponent_width + significand_width)
+}
+
+fn get_scalar_type<'ctx>(context: &ContextRef<'ctx>, scalar_type: &DebugType) -> Option<AnyTypeEnum<'ctx>> {
+    //! LLVM type a scalar of the given Rust type is stored as in memory
+    match &scalar_type.kind {
+        DebugTypeKind::Float if scalar_type.size == 4 => Some(context.f32_type().as_any_type_enum()),
+        DebugTypeKind::Float if scalar_type.size == 8 => Some(context.f64_type().as_any_type_enum()),
+        DebugTypeKind::Int { .. } | DebugTypeKind::Bool | DebugTypeKind::Char | DebugTypeKind::Pointer(_) if scalar_type.size > 0 => {
+            Some(context.custom_width_int_type(u32::try_from(scalar_type.size * 8).ok()?).as_any_type_enum())
+        }
+        _ => None,
+    }
+}
+
+fn get_immediate_part<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    immediate: &Dynamic<'a>,
+    immediate_type: &AnyTypeEnum,
+    offset: u64,
+    scalar_type: AnyTypeEnum<'ctx>,
+) -> Option<(Dynamic<'a>, AnyTypeEnum<'ctx>)> {
+    //! Small aggregates are passed as an integer holding their bytes, from which the scalar at the byte offset is extracted
+    if !immediate_type.is_int_type() {
+        return None;
+    }
+    let low = u32::try_from(offset * 8).ok()?;
+    let width = get_scalar_width(&scalar_type);
+    let immediate_width = get_int_width(immediate_type);
+    if low + width > immediate_width {
+        return None;
+    }
+    let part = if let Some(immediate) = immediate.as_bv() {
+        let part = immediate.extract(low + width - 1, low);
+        if scalar_type.is_float_type() {
+            Dynamic::from_ast(&part.to_int(false))
+        } else {
+            Dynamic::from_ast(&part)
+        }
+    } else {
+        let shifted = get_unsigned_int(solver, &immediate.as_int()?, immediate_width).div(&get_int_from_u128(solver, 1 << low));
+        let bits = shifted.modulo(&get_int_from_u128(solver, 1 << width));
+        if scalar_type.is_float_type() {
+            Dynamic::from_ast(&bits)
+        } else {
+            Dynamic::from_ast(&get_wrapped_signed_int(solver, &bits, width))
+        }
+    };
+    Some((part, scalar_type))
+}
+
+fn read_scalar<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    location: &ValueLocation<'a, 'ctx>,
+    offset: u64,
+    scalar_type: AnyTypeEnum<'ctx>,
+    encoding: IntegerEncoding,
+) -> Option<(Dynamic<'a>, AnyTypeEnum<'ctx>)> {
+    //! Read the scalar of the given type at the byte offset of the value
+    match location {
+        ValueLocation::Memory(memory_var, address) => {
+            let scalar_address = Int::add(solver.get_context(), &[address, &Int::from_u64(solver.get_context(), offset)]);
+            let cell = memory_var.select(&scalar_address).as_int().unwrap();
+            if let Some(float_format) = get_float_format(&scalar_type) {
+                // Memory cells hold the IEEE-754 bits of floats, which are loaded modulo the width of the float
+                let bits = cell.modulo(&get_int_from_u128(solver, 1 << (float_format.0 + float_format.1)));
+                return Some((Dynamic::from_ast(&bits), scalar_type));
+            }
+            Some((get_memory_cell_value(solver, &cell, &scalar_type, encoding)?, scalar_type))
+        }
+        ValueLocation::Immediates(immediates) => {
+            let (immediate_offset, immediate, immediate_type) = immediates.iter().rev().find(|(immediate_offset, _, _)| *immediate_offset <= offset)?;
+            if *immediate_offset != offset || (immediate_type.is_int_type() && get_int_width(immediate_type) > get_scalar_width(&scalar_type)) {
+                return get_immediate_part(solver, immediate, immediate_type, offset - immediate_offset, scalar_type);
+            }
+            // Pointers are addresses, which are printed as integers
+            let value_type = if immediate_type.is_pointer_type() { scalar_type } else { *immediate_type };
+            Some((immediate.clone(), value_type))
+        }
+    }
+}
+
+fn get_literal_string(kind: AggregateKind, name: &str, fields: &[DebugField], field_strings: Vec<String>) -> String {
+    //! Print a struct, tuple struct or tuple literal given the literals of its fields in declaration order
+    let name = get_literal_name(name);
+    match kind {
+        AggregateKind::Struct if field_strings.is_empty() => name.to_string(),
+        AggregateKind::Struct => {
+            let field_strings: Vec<String> = fields.iter().zip(field_strings).map(|(field, field_string)| format!("{}: {}", field.name, field_string)).collect();
+            format!("{} {{ {} }}", name, field_strings.join(", "))
+        }
+        AggregateKind::TupleStruct => format!("{}({})", name, field_strings.join(", ")),
+        AggregateKind::Tuple if field_strings.len() == 1 => format!("({},)", field_strings[0]),
+        AggregateKind::Tuple => format!("({})", field_strings.join(", ")),
+    }
+}
+
+fn get_field_strings<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    model: &Model,
+    context: &ContextRef<'ctx>,
+    location: &ValueLocation<'a, 'ctx>,
+    offset: u64,
+    fields: &[DebugField],
+    encoding: IntegerEncoding,
+) -> Option<Vec<String>> {
+    fields
+        .iter()
+        .map(|field| get_value_string(solver, model, context, location, offset + field.offset, &field.field_type, encoding))
+        .collect()
+}
+
+fn get_enum_value_string<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    model: &Model,
+    context: &ContextRef<'ctx>,
+    location: &ValueLocation<'a, 'ctx>,
+    offset: u64,
+    debug_enum: &DebugEnum,
+    encoding: IntegerEncoding,
+) -> Option<String> {
+    //! Print the variant held by the tag of the enum as a Rust literal
+    let variant = match debug_enum.tag {
+        Some((tag_offset, tag_size)) => {
+            let tag_type = context.custom_width_int_type(u32::try_from(tag_size * 8).ok().filter(|tag_width| *tag_width > 0)?).as_any_type_enum();
+            let (tag, tag_type) = read_scalar(solver, location, offset + tag_offset, tag_type, encoding)?;
+            let tag_value = match get_model_value_string(solver, model, &tag, &tag_type, false).as_str() {
+                "true" => 1,
+                "false" => 0,
+                tag_value => tag_value.parse::<i128>().ok()?,
+            };
+            let variant = debug_enum.get_variant(tag_value, get_scalar_width(&tag_type));
+            if variant.is_none() {
+                warn!("Tag {} does not match any variant of the enum", tag_value);
+            }
+            variant?
+        }
+        None => debug_enum.variants.first()?,
+    };
+    let field_strings = get_field_strings(solver, model, context, location, offset, &variant.fields, encoding)?;
+    Some(get_literal_string(variant.kind, &format!("{}{}", debug_enum.prefix, variant.name), &variant.fields, field_strings))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_elements_string<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    model: &Model,
+    context: &ContextRef<'ctx>,
+    location: &ValueLocation<'a, 'ctx>,
+    offset: u64,
+    element_type: &DebugType,
+    length: u64,
+    encoding: IntegerEncoding,
+) -> Option<String> {
+    //! Print consecutive elements from the byte offset as an array literal
+    let element_strings = (0..length)
+        .map(|i| get_value_string(solver, model, context, location, offset + i * element_type.size, element_type, encoding))
+        .collect::<Option<Vec<String>>>()?;
+    Some(format!("[{}]", element_strings.join(", ")))
+}
+
+fn get_value_string<'a, 'ctx>(
+    solver: &'a Solver<'_>,
+    model: &Model,
+    context: &ContextRef<'ctx>,
+    location: &ValueLocation<'a, 'ctx>,
+    offset: u64,
+    value_type: &DebugType,
+    encoding: IntegerEncoding,
+) -> Option<String> {
+    //! Print the value of the given type at the byte offset as a Rust literal, following the layout rustc chose for it
+    match &value_type.kind {
+        DebugTypeKind::Array(element_type, length) => get_elements_string(solver, model, context, location, offset, element_type, *length, encoding),
+        DebugTypeKind::Aggregate(kind, fields) => {
+            let field_strings = get_field_strings(solver, model, context, location, offset, fields, encoding)?;
+            Some(get_literal_string(*kind, &value_type.name, fields, field_strings))
+        }
+        DebugTypeKind::Enum(debug_enum) => get_enum_value_string(solver, model, context, location, offset, debug_enum, encoding),
+        DebugTypeKind::Slice(_) | DebugTypeKind::Unknown => None,
+        _ => {
+            let (value, scalar_type) = read_scalar(solver, location, offset, get_scalar_type(context, value_type)?, encoding)?;
+            Some(get_scalar_value_string(solver, model, &value, &scalar_type, value_type))
+        }
+    }
+}
+
+fn get_llvm_debug_type(target_data: &TargetData, llvm_type: &AnyTypeEnum) -> DebugType {
+    //! Type of a value without debug info, whose integers are taken as signed and whose structs are taken as tuples
+    let kind = match llvm_type {
+        AnyTypeEnum::IntType(int_type) if int_type.get_bit_width() == 1 => DebugTypeKind::Bool,
+        AnyTypeEnum::IntType(_) => DebugTypeKind::Int { is_unsigned: false },
+        AnyTypeEnum::FloatType(_) => DebugTypeKind::Float,
+        AnyTypeEnum::PointerType(_) => DebugTypeKind::Pointer(Box::new(DebugType::unknown())),
+        AnyTypeEnum::ArrayType(array_type) => {
+            let element_type = get_llvm_debug_type(target_data, &array_type.get_element_type().as_any_type_enum());
+            DebugTypeKind::Array(Box::new(element_type), u64::from(array_type.len()))
+        }
+        AnyTypeEnum::StructType(struct_type) if !struct_type.is_opaque() => {
+            let fields = struct_type
+                .get_field_types()
+                .iter()
+                .enumerate()
+                .map(|(i, field_type)| DebugField {
+                    name: i.to_string(),
+                    offset: target_data.offset_of_element(struct_type, u32::try_from(i).unwrap()).unwrap_or(0),
+                    field_type: get_llvm_debug_type(target_data, &field_type.as_any_type_enum()),
+                })
+                .collect();
+            DebugTypeKind::Aggregate(AggregateKind::Tuple, fields)
+        }
+        // Function and opaque types have no size
+        _ => return DebugType::unknown(),
+    };
+    DebugType {
+        name: String::new(),
+        size: target_data.get_abi_size(llvm_type),
+        kind,
+    }
+}
+
+fn get_immediates_debug_type(target_data: &TargetData, immediates: &[(u64, Dynamic, AnyTypeEnum)]) -> DebugType {
+    //! Type of immediates without debug info, where a pair of immediates is taken as a tuple
+    match immediates {
+        [] => DebugType::unknown(),
+        [(_, _, immediate_type)] => get_llvm_debug_type(target_data, immediate_type),
+        _ => DebugType {
+            name: String::new(),
+            size: 0,
+            kind: DebugTypeKind::Aggregate(
+                AggregateKind::Tuple,
+                immediates
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (offset, _, immediate_type))| DebugField {
+                        name: i.to_string(),
+                        offset: *offset,
+                        field_type: get_llvm_debug_type(target_data, immediate_type),
+                    })
+                    .collect(),
+            ),
+        },
+    }
+}
+
+fn get_reference_prefix(type_name: &str) -> &'static str {
+    if type_name.starts_with("&mut") {
+        "&mut "
+    } else if type_name.starts_with('&') {
+        "&"
+    } else {
+        ""
+    }
+}
+
+fn get_length(model: &Model, length_var: &Dynamic) -> u64 {
+    if let Some(length_var) = length_var.as_bv() {
+        model.eval(&length_var, true).unwrap().as_u64().unwrap()
+    } else {
+        model.eval(&length_var.as_int().unwrap(), true).unwrap().as_u64().unwrap()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_argument_values(
+    module: &AnalysedModule,
+    solver: &Solver,
+    model: &Model,
+    func_arg_names: &[(String, String, BasicTypeEnum)],
+    parameter_types: &[(String, DebugType)],
+    entry_memory_name: &str,
+    encoding: IntegerEncoding,
+) -> Vec<(String, String)> {
+    //! Pair each parameter name with the Rust literal of its value in the model
+    let target_data = module.get_target_data();
+    let context = module.get_context();
+    let memory_var = get_memory_var(solver, entry_memory_name);
+
+    let mut argument_values = Vec::<(String, String)>::new();
+    let mut arguments = func_arg_names.iter().peekable();
+    while let Some((arg_name, z3_name, var_type)) = arguments.next() {
+        let mut parameter_name = arg_name[MAIN_FUNCTION_NAMESPACE.len()..].replace('%', "");
+        let var_type = var_type.as_any_type_enum();
+
+        // Slices are passed as a pointer to their elements followed by their length
+        let slice_length_var = if is_slice_pointer_type(&var_type) {
+            parameter_name = String::from(parameter_name.trim_end_matches(".0"));
+            arguments
+                .next()
+                .and_then(|(_, length_z3_name, length_type)| get_typed_var(solver, length_z3_name, &length_type.as_any_type_enum(), encoding))
+        } else {
+            None
+        };
+
+        // Structs, tuples and enums of two scalars are passed as their two fields
+        let mut immediates = Vec::<(u64, Dynamic, AnyTypeEnum)>::new();
+        if let Some(aggregate_name) = parameter_name.strip_suffix(".0").map(String::from) {
+            let second_arg_name = format!("{}.1", &arg_name[..arg_name.len() - 2]);
+            if let Some((_, second_z3_name, second_var_type)) = arguments.next_if(|(next_arg_name, _, _)| next_arg_name.eq(&second_arg_name)) {
+                parameter_name = aggregate_name;
+                // The second field follows the first at the alignment of its type
+                let second_var_type = second_var_type.as_any_type_enum();
+                let second_alignment = u64::from(target_data.get_abi_alignment(&second_var_type));
+                let second_offset = (target_data.get_abi_size(&var_type) + second_alignment - 1) / second_alignment * second_alignment;
+                for (offset, leaf_z3_name, leaf_type) in [(0, z3_name, var_type), (second_offset, second_z3_name, second_var_type)] {
+                    if let Some(leaf_var) = get_typed_var(solver, leaf_z3_name, &leaf_type, encoding) {
+                        immediates.push((offset, leaf_var, leaf_type));
+                    }
+                }
+            }
+        }
+
+        let parameter_type = parameter_types.iter().find(|(name, _)| name.eq(&parameter_name)).map(|(_, parameter_type)| parameter_type);
+        // Values of at most a pointer, such as an `Option<&T>`, are passed as the pointer itself
+        let is_pointer_value = parameter_type.map_or(false, |parameter_type| {
+            !matches!(parameter_type.kind, DebugTypeKind::Pointer(_) | DebugTypeKind::Slice(_)) && parameter_type.size <= target_data.get_abi_size(&var_type)
+        });
+        let value_string = if var_type.is_pointer_type() && immediates.is_empty() && !is_pointer_value {
+            // References are replayed as a reference to the value they point to in the memory on entry
+            let location = ValueLocation::Memory(memory_var.clone(), Int::new_const(solver.get_context(), z3_name.as_str()));
+            let reference_prefix = parameter_type.map_or("", |parameter_type| get_reference_prefix(&parameter_type.name));
+            let pointee_type = var_type.into_pointer_type().get_element_type();
+            let printed_length = || {
+                let mut length = slice_length_var.as_ref().map_or(0, |length_var| get_length(model, length_var));
+                if length > MAX_PRINTED_SLICE_LENGTH {
+                    warn!("Slice {} of length {} is truncated to its first {} elements", parameter_name, length, MAX_PRINTED_SLICE_LENGTH);
+                    length = MAX_PRINTED_SLICE_LENGTH;
+                }
+                length
+            };
+            let pointee_string = match (parameter_type, pointee_type) {
+                (
+                    Some(DebugType {
+                        kind: DebugTypeKind::Slice(element_type),
+                        ..
+                    }),
+                    _,
+                ) => get_elements_string(solver, model, &context, &location, 0, element_type, printed_length(), encoding),
+                (
+                    Some(DebugType {
+                        kind: DebugTypeKind::Pointer(pointee_type),
+                        ..
+                    }),
+                    _,
+                ) => get_value_string(solver, model, &context, &location, 0, pointee_type, encoding),
+                // Arrays and structs passed by value are passed as a pointer to a copy
+                (Some(parameter_type), _) => get_value_string(solver, model, &context, &location, 0, parameter_type, encoding),
+                (None, AnyTypeEnum::ArrayType(array_type)) if slice_length_var.is_some() => {
+                    let element_type = get_llvm_debug_type(target_data, &array_type.get_element_type().as_any_type_enum());
+                    get_elements_string(solver, model, &context, &location, 0, &element_type, printed_length(), encoding)
+                }
+                (None, _) => get_value_string(solver, model, &context, &location, 0, &get_llvm_debug_type(target_data, &pointee_type), encoding),
+            };
+            pointee_string.map(|pointee_string| format!("{}{}", reference_prefix, pointee_string))
+        } else {
+            if immediates.is_empty() {
+                if let Some(value) = get_typed_var(solver, z3_name.as_str(), &var_type, encoding) {
+                    immediates.push((0, value, var_type));
+                }
+            }
+            let value_type = parameter_type.cloned().unwrap_or_else(|| get_immediates_debug_type(target_data, &immediates));
+            get_value_string(solver, model, &context, &ValueLocation::Immediates(immediates), 0, &value_type, encoding)
+        };
+
+        if let Some(value_string) = value_string {
+            argument_values.push((parameter_name, value_string));
+        } else {
+            warn!("{} is not a supported parameter type!", var_type);
+        }
+    }
+    argument_values
+}
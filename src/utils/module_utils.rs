@@ -0,0 +1,84 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module as InkwellModule;
+use inkwell::targets::TargetData;
+use inkwell::values::FunctionValue;
+
+use crate::codegen::codegen_memory::get_memory_functions;
+use crate::utils::function_utils::get_function_name;
+
+/// The analysed module along with what is derived from it once per analysis, after the module is transformed
+pub struct AnalysedModule<'a, 'ctx> {
+    module: &'a InkwellModule<'ctx>,
+    /// Every block of the module, numbered so a panic site can be recovered from the model however often its function is inlined
+    blocks: Vec<BasicBlock<'ctx>>,
+    block_ids: HashMap<BasicBlock<'ctx>, u64>,
+    target_data: TargetData,
+    /// Names of the functions that read or write memory themselves or through a function they call
+    memory_functions: HashSet<String>,
+    /// Region 0 is reserved for the null pointer
+    next_region: Cell<u64>,
+}
+
+impl<'a, 'ctx> AnalysedModule<'a, 'ctx> {
+    pub fn new(module: &'a InkwellModule<'ctx>) -> Self {
+        let mut blocks = Vec::<BasicBlock<'ctx>>::new();
+        let mut next_function = module.get_first_function();
+        while let Some(current_function) = next_function {
+            blocks.extend(current_function.get_basic_blocks());
+            next_function = current_function.get_next_function();
+        }
+        let block_ids = blocks.iter().enumerate().map(|(id, bb)| (*bb, id as u64)).collect();
+        AnalysedModule {
+            module,
+            blocks,
+            block_ids,
+            target_data: TargetData::create(module.get_data_layout().as_str().to_str().unwrap()),
+            memory_functions: get_memory_functions(module),
+            next_region: Cell::new(1),
+        }
+    }
+
+    pub fn get_panic_site_id(&self, bb: &BasicBlock<'ctx>) -> u64 {
+        //! Blocks added after the analysis started share an id past the last block
+        self.block_ids.get(bb).copied().unwrap_or(self.blocks.len() as u64)
+    }
+
+    pub fn get_panic_site_block(&self, id: u64) -> Option<BasicBlock<'ctx>> {
+        self.blocks.get(usize::try_from(id).ok()?).copied()
+    }
+
+    pub fn get_target_data(&self) -> &TargetData {
+        &self.target_data
+    }
+
+    pub fn get_memory_functions(&self) -> &HashSet<String> {
+        &self.memory_functions
+    }
+
+    pub fn uses_memory(&self, function: &FunctionValue) -> bool {
+        //! Check whether memory needs to be threaded through the function
+        self.memory_functions.contains(&get_function_name(&function.as_global_value().as_pointer_value()))
+    }
+
+    pub fn get_next_region(&self) -> u64 {
+        //! Every allocation gets its own region, counted from the start of the analysis
+        let region = self.next_region.get();
+        self.next_region.set(region + 1);
+        region
+    }
+}
+
+impl<'a, 'ctx> Deref for AnalysedModule<'a, 'ctx> {
+    type Target = InkwellModule<'ctx>;
+
+    fn deref(&self) -> &Self::Target {
+        self.module
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module as InkwellModule;
+use inkwell::values::{AnyValue, BasicValue, BasicValueEnum, InstructionOpcode, InstructionValue, IntValue};
+use inkwell::IntPredicate;
+
+use serde::Serialize;
+
+use crate::utils::function_utils::get_function_name;
+
+pub const PANIC_KINDS: [PanicKind; 8] = [
+    PanicKind::Assert,
+    PanicKind::Overflow,
+    PanicKind::BoundsCheck,
+    PanicKind::Unwrap,
+    PanicKind::DivisionByZero,
+    PanicKind::DivisionOverflow,
+    PanicKind::Explicit,
+    PanicKind::Unreachable,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicKind {
+    Assert,
+    Overflow,
+    BoundsCheck,
+    Unwrap,
+    DivisionByZero,
+    DivisionOverflow,
+    Explicit,
+    Unreachable,
+}
+
+impl PanicKind {
+    pub fn get_id(&self) -> &'static str {
+        //! Stable identifier of the kind, matching its serialized name
+        match self {
+            PanicKind::Assert => "assert",
+            PanicKind::Overflow => "overflow",
+            PanicKind::BoundsCheck => "bounds_check",
+            PanicKind::Unwrap => "unwrap",
+            PanicKind::DivisionByZero => "division_by_zero",
+            PanicKind::DivisionOverflow => "division_overflow",
+            PanicKind::Explicit => "explicit",
+            PanicKind::Unreachable => "unreachable",
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            PanicKind::Assert => "assertion failed",
+            PanicKind::Overflow => "arithmetic overflow",
+            PanicKind::BoundsCheck => "index out of bounds",
+            PanicKind::Unwrap => "unwrap on None or Err",
+            PanicKind::DivisionByZero => "division by zero",
+            PanicKind::DivisionOverflow => "division overflow",
+            PanicKind::Explicit => "explicit panic",
+            PanicKind::Unreachable => "unreachable code",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PanicSite {
+    pub kind: PanicKind,
+    /// Static part of the panic message, if the message is a constant
+    pub message: Option<String>,
+    pub location: Option<SourceLocation>,
+}
+
+impl PanicSite {
+    pub fn get_summary(&self) -> String {
+        //! Describe the panic site on a single line
+        let mut summary = String::from(self.kind.get_description());
+        if let Some(location) = &self.location {
+            summary.push_str(&format!(" at {}:{}:{}", location.file, location.line, location.column));
+        }
+        if let Some(message) = &self.message {
+            summary.push_str(&format!(" ({:?})", message));
+        }
+        summary
+    }
+}
+
+// Panic functions that identify the kind of panic by themselves, checked by substring since their paths vary between compiler versions
+const PANIC_FUNCTION_KINDS: [(&str, PanicKind); 15] = [
+    ("panic_bounds_check", PanicKind::BoundsCheck),
+    ("panic_const_div_by_zero", PanicKind::DivisionByZero),
+    ("panic_const_rem_by_zero", PanicKind::DivisionByZero),
+    ("panic_const_div_overflow", PanicKind::DivisionOverflow),
+    ("panic_const_rem_overflow", PanicKind::DivisionOverflow),
+    ("panic_const_add_overflow", PanicKind::Overflow),
+    ("panic_const_sub_overflow", PanicKind::Overflow),
+    ("panic_const_mul_overflow", PanicKind::Overflow),
+    ("panic_const_neg_overflow", PanicKind::Overflow),
+    ("panic_const_shl_overflow", PanicKind::Overflow),
+    ("panic_const_shr_overflow", PanicKind::Overflow),
+    ("core::option::unwrap_failed", PanicKind::Unwrap),
+    ("core::option::expect_failed", PanicKind::Unwrap),
+    ("core::result::unwrap_failed", PanicKind::Unwrap),
+    ("core::panicking::assert_failed", PanicKind::Assert),
+];
+
+// Fragments of the constant messages older compilers pass to the generic panic functions, checked in order
+const PANIC_MESSAGE_KINDS: [(&str, PanicKind); 8] = [
+    ("divide by zero", PanicKind::DivisionByZero),
+    ("divisor of zero", PanicKind::DivisionByZero),
+    ("divide with overflow", PanicKind::DivisionOverflow),
+    ("remainder with overflow", PanicKind::DivisionOverflow),
+    ("with overflow", PanicKind::Overflow),
+    ("assertion failed", PanicKind::Assert),
+    ("entered unreachable code", PanicKind::Unreachable),
+    ("on a `None` value", PanicKind::Unwrap),
+];
+
+fn get_called_function_name(instruction: &InstructionValue) -> Option<String> {
+    if instruction.get_opcode() != InstructionOpcode::Call {
+        return None;
+    }
+    let call_operand = instruction.get_operand(instruction.get_num_operands() - 1)?.left()?;
+    if !call_operand.is_pointer_value() {
+        return None;
+    }
+    Some(get_function_name(&call_operand.into_pointer_value()))
+}
+
+fn get_referenced_global_initializer(module: &InkwellModule, value_string: &str) -> Option<String> {
+    //! Print the initializer of the first global referenced in the printed value
+    let global_name = &value_string[value_string.find('@')? + 1..];
+    let global_name = match global_name.strip_prefix('"') {
+        Some(quoted_name) => &quoted_name[..quoted_name.find('"')?],
+        None => &global_name[..global_name.find(|c: char| c == ' ' || c == ',' || c == ')' || c == '}' || c == ']').unwrap_or(global_name.len())],
+    };
+    let initializer = module.get_global(global_name)?.get_initializer()?;
+    Some(initializer.print_to_string().to_string())
+}
+
+fn get_constant_bytes(initializer: &str) -> Option<Vec<u8>> {
+    //! Decode the first byte string constant (`c"..."`) of the printed initializer
+    let literal = &initializer[initializer.find("c\"")? + 2..];
+    let mut bytes = Vec::<u8>::new();
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(bytes),
+            '\\' => {
+                // Quotes, backslashes and non-printable bytes are escaped as two hex digits
+                let escape: String = chars.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&escape, 16).ok()?);
+            }
+            _ => {
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+    None
+}
+
+fn get_str_constant(module: &InkwellModule, value: &BasicValueEnum) -> Option<(String, Vec<u8>)> {
+    //! Read the `&'static str` a constant points to, along with the bytes of the fields that follow it (such as a source location)
+    let initializer = get_referenced_global_initializer(module, &value.print_to_string().to_string())?;
+    if !initializer.contains('@') {
+        // A pointer to the bytes of the string itself
+        return Some((String::from_utf8_lossy(&get_constant_bytes(&initializer)?).to_string(), Vec::new()));
+    }
+    // A pointer to a string slice, whose length is stored after the pointer to its bytes
+    let string_bytes = get_constant_bytes(&get_referenced_global_initializer(module, &initializer)?)?;
+    let field_bytes = get_constant_bytes(&initializer)?;
+    let length = u64::from_le_bytes(field_bytes.get(..8)?.try_into().ok()?);
+    let string_bytes = string_bytes.get(..usize::try_from(length).ok()?)?;
+    Some((String::from_utf8_lossy(string_bytes).to_string(), field_bytes[8..].to_vec()))
+}
+
+pub fn get_panic_site(module: &InkwellModule, bb: &BasicBlock) -> PanicSite {
+    //! Classify a panicking block by the panic function it calls and the constant message and `Location` passed to it
+    let mut kind = None;
+    let mut is_panic_call = false;
+    let mut message = None;
+    let mut location = None;
+
+    let mut next_instruction = bb.get_first_instruction();
+    while let Some(current_instruction) = next_instruction {
+        next_instruction = current_instruction.get_next_instruction();
+        let called_function_name = match get_called_function_name(&current_instruction) {
+            Some(called_function_name) if !called_function_name.starts_with("llvm.") => called_function_name,
+            _ => continue,
+        };
+        if let Some((_, function_kind)) = PANIC_FUNCTION_KINDS.iter().find(|(name, _)| called_function_name.contains(name)) {
+            kind = kind.or(Some(*function_kind));
+        }
+        is_panic_call |= called_function_name.contains("panicking::") || called_function_name.contains("begin_panic");
+
+        // The message (or its formatting pieces) is passed before the location, which is always the last argument
+        for i in 0..current_instruction.get_num_operands() - 1 {
+            let operand = match current_instruction.get_operand(i).and_then(|operand| operand.left()) {
+                Some(operand) if operand.is_pointer_value() => operand,
+                _ => continue,
+            };
+            match get_str_constant(module, &operand) {
+                Some((file, field_bytes)) if field_bytes.len() >= 8 => {
+                    location = location.or(Some(SourceLocation {
+                        file,
+                        line: u32::from_le_bytes(field_bytes[0..4].try_into().unwrap()),
+                        column: u32::from_le_bytes(field_bytes[4..8].try_into().unwrap()),
+                    }));
+                }
+                Some((text, _)) => message = message.or(Some(text)),
+                None => {}
+            }
+        }
+    }
+
+    let message_kind = message
+        .as_ref()
+        .and_then(|message| PANIC_MESSAGE_KINDS.iter().find(|(fragment, _)| message.contains(fragment)).map(|(_, kind)| *kind));
+    let kind = kind
+        .or(message_kind)
+        .unwrap_or(if is_panic_call || message.is_some() { PanicKind::Explicit } else { PanicKind::Unreachable });
+    PanicSite { kind, message, location }
+}
+
+pub fn get_compared_operand<'a>(condition: &BasicValueEnum<'a>, is_compared_constant: &dyn Fn(&IntValue) -> bool) -> Option<BasicValueEnum<'a>> {
+    //! Find the value that the branch condition of a panic check compares for equality with a matching constant
+    let instruction = condition.as_instruction_value()?;
+    match instruction.get_opcode() {
+        InstructionOpcode::Call if get_called_function_name(&instruction).map_or(false, |name| name.starts_with("llvm.expect")) => {
+            get_compared_operand(&instruction.get_operand(0)?.left()?, is_compared_constant)
+        }
+        InstructionOpcode::And => {
+            get_compared_operand(&instruction.get_operand(0)?.left()?, is_compared_constant).or_else(|| get_compared_operand(&instruction.get_operand(1)?.left()?, is_compared_constant))
+        }
+        InstructionOpcode::ICmp if instruction.get_icmp_predicate() == Some(IntPredicate::EQ) => {
+            let operand1 = instruction.get_operand(0)?.left()?;
+            let operand2 = instruction.get_operand(1)?.left()?;
+            let is_constant = |operand: &BasicValueEnum| operand.is_int_value() && operand.into_int_value().is_const() && is_compared_constant(&operand.into_int_value());
+            if is_constant(&operand2) {
+                Some(operand1)
+            } else if is_constant(&operand1) {
+                Some(operand2)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
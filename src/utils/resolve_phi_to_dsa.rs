@@ -10,6 +10,9 @@ use inkwell::context::Context;
 use inkwell::module::Module as InkwellModule;
 use inkwell::values::{AnyValue, BasicValueEnum, InstructionOpcode, InstructionValue, IntValue, PhiValue, PointerValue};
 
+// Suffix of the allocas holding resolved phi values, which cannot clash with names generated by rustc
+pub const PHI_ALLOCA_SUFFIX: &str = ".phi_ptr";
+
 struct Assignment<'a> {
     lvalue: String,
     rvalue: BasicValueEnum<'a>,
@@ -78,7 +81,7 @@ pub fn resolve_phi_to_dsa(context: &Context, module: &InkwellModule) {
                     if current_function.get_first_basic_block().unwrap().get_first_instruction().is_some() {
                         builder.position_before(&current_function.get_first_basic_block().unwrap().get_first_instruction().unwrap());
                     }
-                    let alloca = builder.build_alloca(phi_instruction_type, &format!("{}_ptr", var_name));
+                    let alloca = builder.build_alloca(phi_instruction_type, &format!("{}{}", var_name, PHI_ALLOCA_SUFFIX));
                     allocas.insert(var_name.clone(), alloca);
 
                     // Load resolved value of phi instruction
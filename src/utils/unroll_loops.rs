@@ -0,0 +1,462 @@
+// Copyright (c) 2023 Benjamin Jialong Wu
+// This code is licensed under MIT license (see LICENSE.md for details)
+
+use std::collections::{HashMap, HashSet};
+
+use either::Either;
+use tracing::warn;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::builder::Builder;
+use inkwell::context::Context;
+use inkwell::module::Module as InkwellModule;
+use inkwell::passes::PassManager;
+use inkwell::types::{AnyType, BasicType, BasicTypeEnum, FunctionType};
+use inkwell::values::{AnyValue, AnyValueEnum, BasicValue, BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue, IntValue, PointerValue};
+
+use crate::codegen::codegen_memory::{get_memory_functions, is_memory_write};
+use crate::utils::function_utils::get_function_name;
+
+pub const DEFAULT_UNROLL_BOUND: u32 = 10;
+pub const BOUND_EXHAUSTED_BLOCK_NAME: &str = "wombat_symx_bound_exhausted";
+pub const LOOP_CUT_BLOCK_NAME: &str = "wombat_symx_loop_cut";
+pub const INVARIANT_FUNCTION_NAME: &str = "wombat_symx::invariant";
+pub const ASSERT_INVARIANT_FUNCTION_NAME: &str = "wombat_symx_assert_invariant";
+pub const ASSUME_INVARIANT_FUNCTION_NAME: &str = "wombat_symx_assume_invariant";
+pub const CUT_INVARIANT_FUNCTION_NAME: &str = "wombat_symx_assert_invariant_and_cut";
+pub const NONDET_FUNCTION_NAME: &str = "wombat_symx_nondet";
+pub const HAVOC_MEMORY_FUNCTION_NAME: &str = "wombat_symx_havoc_memory";
+
+type InstructionMap<'a> = HashMap<InstructionValue<'a>, InstructionValue<'a>>;
+
+pub fn is_bound_exhausted_block(bb: &BasicBlock) -> bool {
+    // LLVM makes block names unique by appending a number, so only the prefix is checked
+    bb.get_name().to_str().unwrap().starts_with(BOUND_EXHAUSTED_BLOCK_NAME)
+}
+
+pub fn is_loop_cut_block(bb: &BasicBlock) -> bool {
+    bb.get_name().to_str().unwrap().starts_with(LOOP_CUT_BLOCK_NAME)
+}
+
+fn get_successors(bb: BasicBlock) -> Vec<BasicBlock> {
+    let mut successors = Vec::new();
+    if let Some(terminator) = bb.get_terminator() {
+        for i in 0..terminator.get_num_operands() {
+            if let Some(Either::Right(successor)) = terminator.get_operand(i) {
+                if !successors.contains(&successor) {
+                    successors.push(successor);
+                }
+            }
+        }
+    }
+    successors
+}
+
+fn get_predecessors<'a>(function: &FunctionValue<'a>) -> HashMap<BasicBlock<'a>, Vec<BasicBlock<'a>>> {
+    let mut predecessors = HashMap::<BasicBlock, Vec<BasicBlock>>::new();
+    for bb in function.get_basic_blocks() {
+        for successor in get_successors(bb) {
+            predecessors.entry(successor).or_insert_with(Vec::new).push(bb);
+        }
+    }
+    predecessors
+}
+
+fn visit<'a>(bb: BasicBlock<'a>, visited: &mut HashSet<BasicBlock<'a>>, on_stack: &mut HashSet<BasicBlock<'a>>, back_edges: &mut Vec<(BasicBlock<'a>, BasicBlock<'a>)>) {
+    visited.insert(bb);
+    on_stack.insert(bb);
+    for successor in get_successors(bb) {
+        if on_stack.contains(&successor) {
+            back_edges.push((bb, successor));
+        } else if !visited.contains(&successor) {
+            visit(successor, visited, on_stack, back_edges);
+        }
+    }
+    on_stack.remove(&bb);
+}
+
+fn get_back_edges<'a>(function: &FunctionValue<'a>) -> Vec<(BasicBlock<'a>, BasicBlock<'a>)> {
+    //! Depth first search from the entry block where an edge to a block still on the stack closes a cycle
+    let mut back_edges = Vec::new();
+    if let Some(entry) = function.get_first_basic_block() {
+        visit(entry, &mut HashSet::new(), &mut HashSet::new(), &mut back_edges);
+    }
+    back_edges
+}
+
+fn get_innermost_loop<'a>(function: &FunctionValue<'a>) -> Option<(BasicBlock<'a>, Vec<BasicBlock<'a>>)> {
+    //! Find a loop header and its natural loop body which contains no other loop
+    let back_edges = get_back_edges(function);
+    let predecessors = get_predecessors(function);
+
+    let mut headers = Vec::<BasicBlock>::new();
+    for (_, header) in &back_edges {
+        if !headers.contains(header) {
+            headers.push(*header);
+        }
+    }
+
+    for header in &headers {
+        // The natural loop is every block that reaches a latch without passing through the header
+        let mut body = HashSet::from([*header]);
+        let mut worklist: Vec<BasicBlock> = back_edges.iter().filter(|(_, target)| target == header).map(|(latch, _)| *latch).collect();
+        while let Some(bb) = worklist.pop() {
+            if body.insert(bb) {
+                if let Some(bb_predecessors) = predecessors.get(&bb) {
+                    worklist.extend(bb_predecessors);
+                }
+            }
+        }
+
+        if headers.iter().all(|other| other == header || !body.contains(other)) {
+            let ordered_body = function.get_basic_blocks().into_iter().filter(|bb| body.contains(bb)).collect();
+            return Some((*header, ordered_body));
+        }
+    }
+
+    None
+}
+
+fn get_basic_value(instruction: InstructionValue) -> Option<BasicValueEnum> {
+    match instruction.as_any_value_enum() {
+        AnyValueEnum::ArrayValue(value) => Some(value.into()),
+        AnyValueEnum::IntValue(value) => Some(value.into()),
+        AnyValueEnum::FloatValue(value) => Some(value.into()),
+        AnyValueEnum::PointerValue(value) => Some(value.into()),
+        AnyValueEnum::StructValue(value) => Some(value.into()),
+        AnyValueEnum::VectorValue(value) => Some(value.into()),
+        _ => None,
+    }
+}
+
+fn remap_value<'a>(value: BasicValueEnum<'a>, instruction_map: &InstructionMap<'a>) -> BasicValueEnum<'a> {
+    //! Replace a value defined in the original loop body by its copy
+    value
+        .as_instruction_value()
+        .and_then(|instruction| instruction_map.get(&instruction))
+        .and_then(|copy| get_basic_value(*copy))
+        .unwrap_or(value)
+}
+
+fn is_debug_intrinsic(instruction: &InstructionValue) -> bool {
+    instruction.get_opcode() == InstructionOpcode::Call && instruction.print_to_string().to_str().unwrap().contains("@llvm.dbg.")
+}
+
+fn clone_instruction<'a>(builder: &Builder<'a>, instruction: InstructionValue<'a>, instruction_map: &mut InstructionMap<'a>) {
+    let copy = instruction.explicit_clone();
+    for i in 0..copy.get_num_operands() {
+        if let Some(Either::Left(operand)) = copy.get_operand(i) {
+            copy.set_operand(i, remap_value(operand, instruction_map));
+        }
+    }
+    let name = instruction.get_name().and_then(|name| name.to_str().ok()).filter(|name| !name.is_empty());
+    builder.insert_instruction(&copy, name);
+    instruction_map.insert(instruction, copy);
+}
+
+fn get_unrolled_target<'a>(
+    target: BasicBlock<'a>,
+    header: BasicBlock<'a>,
+    copy_index: usize,
+    copies: &[HashMap<BasicBlock<'a>, BasicBlock<'a>>],
+    final_block: BasicBlock<'a>,
+    exit_target: Option<BasicBlock<'a>>,
+) -> BasicBlock<'a> {
+    if target == header {
+        // Back edges enter the next iteration, or the final block after the last one
+        copies.get(copy_index + 1).map_or(final_block, |next_copy| *next_copy.get(&header).unwrap())
+    } else {
+        // Edges leaving the loop keep their original target unless the exits of the block are redirected
+        copies[copy_index].get(&target).copied().unwrap_or_else(|| exit_target.unwrap_or(target))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn copy_loop<'a>(
+    context: &'a Context,
+    builder: &Builder<'a>,
+    function: FunctionValue<'a>,
+    header: BasicBlock<'a>,
+    body: &[BasicBlock<'a>],
+    num_copies: usize,
+    final_block: BasicBlock<'a>,
+    redirected_exits: &HashMap<(usize, BasicBlock<'a>), BasicBlock<'a>>,
+) -> Vec<HashMap<BasicBlock<'a>, BasicBlock<'a>>> {
+    //! Replace a loop by num_copies copies of its body chained through the back edges, with the last back edges leading to final_block
+    //! and the edges leaving the loop from a block of a copy in redirected_exits leading to the given block instead
+
+    // The original blocks are the first iteration
+    let mut copies = vec![body.iter().map(|bb| (*bb, *bb)).collect::<HashMap<BasicBlock, BasicBlock>>()];
+    let mut instruction_maps = vec![InstructionMap::new()];
+    for copy_index in 1..num_copies {
+        let mut copy = HashMap::new();
+        let mut instruction_map = InstructionMap::new();
+        for bb in body {
+            let copy_bb = context.append_basic_block(function, &format!("{}.unroll{}", bb.get_name().to_str().unwrap(), copy_index));
+            builder.position_at_end(copy_bb);
+            let mut next_instruction = bb.get_first_instruction();
+            while let Some(current_instruction) = next_instruction {
+                if Some(current_instruction) != bb.get_terminator() && !is_debug_intrinsic(&current_instruction) {
+                    clone_instruction(builder, current_instruction, &mut instruction_map);
+                }
+                next_instruction = current_instruction.get_next_instruction();
+            }
+            copy.insert(*bb, copy_bb);
+        }
+        copies.push(copy);
+        instruction_maps.push(instruction_map);
+    }
+
+    // Rewire terminators, leaving the original terminators in place until every copy has been built from them
+    for copy_index in (0..num_copies).rev() {
+        let instruction_map = instruction_maps.get_mut(copy_index).unwrap();
+        for bb in body {
+            let copy_bb = *copies[copy_index].get(bb).unwrap();
+            let exit_target = redirected_exits.get(&(copy_index, *bb)).copied();
+            let terminator = bb.get_terminator().unwrap();
+            let num_operands = terminator.get_num_operands();
+            match terminator.get_opcode() {
+                InstructionOpcode::Br if num_operands == 1 => {
+                    let target = get_unrolled_target(terminator.get_operand(0).unwrap().right().unwrap(), header, copy_index, &copies, final_block, exit_target);
+                    if copy_index == 0 {
+                        terminator.remove_from_basic_block();
+                    }
+                    builder.position_at_end(copy_bb);
+                    builder.build_unconditional_branch(target);
+                }
+                InstructionOpcode::Br if num_operands == 3 => {
+                    let condition = remap_value(terminator.get_operand(0).unwrap().left().unwrap(), instruction_map).into_int_value();
+                    let else_block = get_unrolled_target(terminator.get_operand(1).unwrap().right().unwrap(), header, copy_index, &copies, final_block, exit_target);
+                    let then_block = get_unrolled_target(terminator.get_operand(2).unwrap().right().unwrap(), header, copy_index, &copies, final_block, exit_target);
+                    if copy_index == 0 {
+                        terminator.remove_from_basic_block();
+                    }
+                    builder.position_at_end(copy_bb);
+                    builder.build_conditional_branch(condition, then_block, else_block);
+                }
+                InstructionOpcode::Switch => {
+                    let condition = remap_value(terminator.get_operand(0).unwrap().left().unwrap(), instruction_map).into_int_value();
+                    let else_block = get_unrolled_target(terminator.get_operand(1).unwrap().right().unwrap(), header, copy_index, &copies, final_block, exit_target);
+                    let mut cases = Vec::<(IntValue, BasicBlock)>::new();
+                    for i in 2..num_operands {
+                        if i % 2 == 0 {
+                            let case_value = terminator.get_operand(i).unwrap().left().unwrap().into_int_value();
+                            let case_block = get_unrolled_target(terminator.get_operand(i + 1).unwrap().right().unwrap(), header, copy_index, &copies, final_block, exit_target);
+                            cases.push((case_value, case_block));
+                        }
+                    }
+                    if copy_index == 0 {
+                        terminator.remove_from_basic_block();
+                    }
+                    builder.position_at_end(copy_bb);
+                    builder.build_switch(condition, else_block, &cases);
+                }
+                InstructionOpcode::Return | InstructionOpcode::Unreachable => {
+                    if copy_index != 0 {
+                        builder.position_at_end(copy_bb);
+                        clone_instruction(builder, terminator, instruction_map);
+                    }
+                }
+                opcode => {
+                    warn!("Opcode {:?} is not supported as a terminator for loop unrolling", opcode);
+                }
+            }
+        }
+    }
+    copies
+}
+
+pub fn is_invariant_function_name(function_name: &str) -> bool {
+    //! Check for the wombat_symx::invariant marker by its path, given with or without the hash rustc appends to it,
+    //! which is either this crate's marker or one defined in a `wombat_symx` module of the analysed crate
+    let path = match function_name.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => path,
+        _ => function_name,
+    };
+    path == INVARIANT_FUNCTION_NAME || path.ends_with(&format!("::{}", INVARIANT_FUNCTION_NAME))
+}
+
+fn is_invariant_call(instruction: &InstructionValue) -> bool {
+    //! Check for a call to the wombat_symx::invariant marker which has not been assigned a role yet
+    instruction.get_opcode() == InstructionOpcode::Call
+        && matches!(
+            instruction.get_operand(instruction.get_num_operands() - 1),
+            Some(Either::Left(BasicValueEnum::PointerValue(callee))) if is_invariant_function_name(&get_function_name(&callee))
+        )
+}
+
+fn get_instructions(bb: BasicBlock) -> Vec<InstructionValue> {
+    let mut instructions = Vec::new();
+    let mut next_instruction = bb.get_first_instruction();
+    while let Some(current_instruction) = next_instruction {
+        instructions.push(current_instruction);
+        next_instruction = current_instruction.get_next_instruction();
+    }
+    instructions
+}
+
+fn get_marker_function<'a>(module: &InkwellModule<'a>, name: &str, fn_type: FunctionType<'a>) -> FunctionValue<'a> {
+    module.get_function(name).unwrap_or_else(|| module.add_function(name, fn_type, None))
+}
+
+fn replace_invariant_calls<'a>(context: &'a Context, module: &InkwellModule<'a>, builder: &Builder<'a>, bb: BasicBlock<'a>, marker_function_name: &str) {
+    //! Replace the invariant marker calls in a block by calls giving the invariant its role in this loop copy
+    let marker_function = get_marker_function(module, marker_function_name, context.void_type().fn_type(&[context.bool_type().into()], false));
+    for instruction in get_instructions(bb) {
+        if is_invariant_call(&instruction) {
+            let condition = instruction.get_operand(0).unwrap().left().unwrap();
+            builder.position_before(&instruction);
+            builder.build_call(marker_function, &[condition.into()], "");
+            instruction.remove_from_basic_block();
+        }
+    }
+}
+
+fn havoc_loop_variables<'a>(context: &'a Context, module: &InkwellModule<'a>, memory_functions: &HashSet<String>, builder: &Builder<'a>, body: &[BasicBlock<'a>], header: BasicBlock<'a>) {
+    //! Overwrite every local variable the loop stores to with a nondeterministic value at the start of the header,
+    //! and start from a fresh version of the memory if the loop writes to memory through pointers, intrinsics or calls
+    let mut havoc_targets = Vec::<(PointerValue, BasicTypeEnum)>::new();
+    let mut is_memory_written = false;
+    for bb in body {
+        for instruction in get_instructions(*bb) {
+            let is_local_store = instruction.get_opcode() == InstructionOpcode::Store
+                && instruction
+                    .get_operand(1)
+                    .unwrap()
+                    .left()
+                    .unwrap()
+                    .into_pointer_value()
+                    .as_instruction()
+                    .map_or(false, |allocation| allocation.get_opcode() == InstructionOpcode::Alloca);
+            if is_local_store {
+                let value_type = instruction.get_operand(0).unwrap().left().unwrap().get_type();
+                let pointer = instruction.get_operand(1).unwrap().left().unwrap().into_pointer_value();
+                if !havoc_targets.iter().any(|(target, _)| *target == pointer) {
+                    havoc_targets.push((pointer, value_type));
+                }
+            } else {
+                is_memory_written |= is_memory_write(module, memory_functions, &instruction);
+            }
+        }
+    }
+
+    builder.position_before(&header.get_first_instruction().unwrap());
+    if is_memory_written {
+        let havoc_memory_function = get_marker_function(module, HAVOC_MEMORY_FUNCTION_NAME, context.void_type().fn_type(&[], false));
+        builder.build_call(havoc_memory_function, &[], "");
+    }
+    for (pointer, value_type) in havoc_targets {
+        let nondet_function_name = format!("{}.{}", NONDET_FUNCTION_NAME, value_type.print_to_string().to_str().unwrap());
+        let nondet_function = get_marker_function(module, &nondet_function_name, value_type.fn_type(&[], false));
+        let nondet_value = builder.build_call(nondet_function, &[], "havoc").try_as_basic_value().left().unwrap();
+        builder.build_store(pointer, nondet_value);
+    }
+}
+
+fn get_blocks_before_invariant<'a>(header: BasicBlock<'a>, body: &[BasicBlock<'a>], invariant_block: BasicBlock<'a>) -> HashSet<BasicBlock<'a>> {
+    //! Blocks an iteration can run before it reaches the block of the invariant, such as the condition of a while loop
+    let mut blocks = HashSet::new();
+    let mut worklist = vec![header];
+    while let Some(bb) = worklist.pop() {
+        if bb != invariant_block && blocks.insert(bb) {
+            worklist.extend(get_successors(bb).into_iter().filter(|successor| *successor != header && body.contains(successor)));
+        }
+    }
+    blocks
+}
+
+fn get_invariant_block<'a>(header: BasicBlock<'a>, body: &[BasicBlock<'a>]) -> Option<BasicBlock<'a>> {
+    //! The block of the first invariant marker of the loop, provided every iteration passes through it
+    let invariant_block = *body.iter().find(|bb| get_instructions(**bb).iter().any(is_invariant_call))?;
+    let is_on_every_iteration = get_blocks_before_invariant(header, body, invariant_block).into_iter().all(|bb| !get_successors(bb).contains(&header));
+    if is_on_every_iteration {
+        Some(invariant_block)
+    } else {
+        warn!("Invariant in loop {:?} is skipped by some iteration, so the loop is unrolled instead", header.get_name());
+        None
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cut_loop<'a>(
+    context: &'a Context,
+    module: &InkwellModule<'a>,
+    memory_functions: &HashSet<String>,
+    builder: &Builder<'a>,
+    function: FunctionValue<'a>,
+    header: BasicBlock<'a>,
+    body: &[BasicBlock<'a>],
+    invariant_block: BasicBlock<'a>,
+) {
+    //! Replace a loop annotated with an invariant by three copies of its body:
+    //! the first iteration asserts the invariant (base case),
+    //! an arbitrary iteration starts from havocked variables and assumes the invariant,
+    //! and the iteration after it asserts the invariant again (inductive step) before the path is cut
+    let loop_cut_block = context.append_basic_block(function, LOOP_CUT_BLOCK_NAME);
+    builder.position_at_end(loop_cut_block);
+    builder.build_unreachable();
+
+    // Before its invariant the arbitrary iteration runs on havocked variables the invariant is not assumed of yet,
+    // so its exits and panics there are cut, which loses nothing as the iteration after it covers them
+    let redirected_exits = get_blocks_before_invariant(header, body, invariant_block).into_iter().map(|bb| ((1, bb), loop_cut_block)).collect();
+    let copies = copy_loop(context, builder, function, header, body, 3, loop_cut_block, &redirected_exits);
+    let marker_function_names = [ASSERT_INVARIANT_FUNCTION_NAME, ASSUME_INVARIANT_FUNCTION_NAME, CUT_INVARIANT_FUNCTION_NAME];
+    for (copy, marker_function_name) in copies.iter().zip(marker_function_names) {
+        for bb in body {
+            replace_invariant_calls(context, module, builder, *copy.get(bb).unwrap(), marker_function_name);
+        }
+    }
+    havoc_loop_variables(context, module, memory_functions, builder, body, *copies[1].get(&header).unwrap());
+}
+
+fn cut_irreducible_loops<'a>(context: &'a Context, builder: &Builder<'a>, function: FunctionValue<'a>) {
+    //! A cycle entered through more than one block has no header to unroll from, so each of its
+    //! back edges leads to a bound exhausted block instead and the verdict is unknown rather than safe
+    let back_edges = get_back_edges(&function);
+    if back_edges.is_empty() {
+        return;
+    }
+    warn!("Irreducible loop in function {:?} is cut off as bound exhausted after its first iteration", function.get_name());
+    let bound_exhausted_block = context.append_basic_block(function, BOUND_EXHAUSTED_BLOCK_NAME);
+    builder.position_at_end(bound_exhausted_block);
+    builder.build_unreachable();
+    for (latch, target) in back_edges {
+        // A single copy of the latch alone rewires only its edges to the target
+        copy_loop(context, builder, function, target, &[latch], 1, bound_exhausted_block, &HashMap::new());
+    }
+}
+
+pub fn unroll_loops<'a>(context: &'a Context, module: &InkwellModule<'a>, unroll_bound: u32) {
+    //! Transform every loop into an acyclic CFG, either by cutting it at its invariant
+    //! or by unrolling it so iterations past unroll_bound reach a bound exhausted block
+    let builder = context.create_builder();
+    let pass_manager = PassManager::create(module);
+    pass_manager.add_demote_memory_to_register_pass();
+
+    let mut loop_functions = Vec::<FunctionValue>::new();
+    let mut next_function = module.get_first_function();
+    while let Some(current_function) = next_function {
+        if !get_back_edges(&current_function).is_empty() {
+            // Demote values used across blocks to memory so a loop body can be copied one block at a time
+            pass_manager.run_on(&current_function);
+            loop_functions.push(current_function);
+        }
+        next_function = current_function.get_next_function();
+    }
+
+    // Copying and cutting loops does not change which functions use memory, so it is only computed once they are demoted
+    let memory_functions = get_memory_functions(module);
+    for current_function in loop_functions {
+        while let Some((header, body)) = get_innermost_loop(&current_function) {
+            if let Some(invariant_block) = get_invariant_block(header, &body) {
+                cut_loop(context, module, &memory_functions, &builder, current_function, header, &body, invariant_block);
+            } else {
+                let bound_exhausted_block = context.append_basic_block(current_function, BOUND_EXHAUSTED_BLOCK_NAME);
+                builder.position_at_end(bound_exhausted_block);
+                builder.build_unreachable();
+                copy_loop(context, &builder, current_function, header, &body, unroll_bound as usize, bound_exhausted_block, &HashMap::new());
+            }
+        }
+        cut_irreducible_loops(context, &builder, current_function);
+    }
+}
@@ -1,31 +1,321 @@
 // Copyright (c) 2023 Benjamin Jialong Wu
 // This code is licensed under MIT license (see LICENSE.md for details)
 
-use inkwell::values::AnyValue;
+use std::str::FromStr;
 
-use z3::ast::{Ast, Bool, Int};
+use tracing::warn;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module as InkwellModule;
+use inkwell::types::{AnyType, AnyTypeEnum};
+use inkwell::values::{AnyValue, BasicValueEnum, InstructionValue, PointerValue};
+
+use z3::ast::{Ast, Bool, Dynamic, Float, Int, BV};
 use z3::Solver;
 
+use crate::error::WombatError;
+
 pub const CONST_NAMESPACE: &str = "const_";
 
-pub fn get_var_name<'a>(value: &dyn AnyValue, solver: &'a Solver<'_>, namespace: &str) -> String {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerEncoding {
+    /// Model every LLVM integer as an unbounded Z3 Int with range constraints on inputs and overflow checks
+    ///
+    /// LLVM integers carry no sign, so each Int holds the two's complement (signed) reading of the bits.
+    /// Unsigned predicates, intrinsics and casts convert with `get_unsigned_int` where needed.
+    Int,
+    /// Model every LLVM iN as a Z3 bitvector of width N for exact wraparound and bitwise semantics
+    BitVector,
+}
+
+impl FromStr for IntegerEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "int" => Ok(IntegerEncoding::Int),
+            "bv" | "bitvector" => Ok(IntegerEncoding::BitVector),
+            _ => Err(format!("Unknown integer encoding {:?}, expected \"int\" or \"bv\"", s)),
+        }
+    }
+}
+
+pub fn get_int_width(var_type: &AnyTypeEnum) -> u32 {
+    var_type.into_int_type().get_bit_width()
+}
+
+pub fn get_float_format(var_type: &AnyTypeEnum) -> Option<(u32, u32)> {
+    //! Exponent and significand widths (including the hidden bit) of the IEEE-754 format of f32 and f64
+    match var_type {
+        AnyTypeEnum::FloatType(float_type) => match float_type.print_to_string().to_str().unwrap() {
+            "float" => Some((8, 24)),
+            "double" => Some((11, 53)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn get_float_sort_string((exponent_width, significand_width): (u32, u32)) -> String {
+    format!("(_ FloatingPoint {} {})", exponent_width, significand_width)
+}
+
+pub fn get_float_bits_term(bits: u64, (exponent_width, significand_width): (u32, u32)) -> String {
+    //! SMT-LIB literal of the float with the given IEEE-754 bit pattern, which is exact for every value including NaN
+    let mantissa_width = significand_width - 1;
+    let sign = bits >> (exponent_width + mantissa_width) & 1;
+    let exponent = bits >> mantissa_width & ((1 << exponent_width) - 1);
+    let mantissa = bits & ((1 << mantissa_width) - 1);
+    format!(
+        "(fp #b{} #b{:0exponent_width$b} #b{:0mantissa_width$b})",
+        sign,
+        exponent,
+        mantissa,
+        exponent_width = exponent_width as usize,
+        mantissa_width = mantissa_width as usize
+    )
+}
+
+fn get_float_constant_bits(const_value_str: &str, float_format: (u32, u32)) -> Option<u64> {
+    //! LLVM prints float constants as decimals when exact and otherwise as the hexadecimal bits of the equal double
+    let value = if let Some(hex_bits) = const_value_str.strip_prefix("0x") {
+        f64::from_bits(u64::from_str_radix(hex_bits, 16).ok()?)
+    } else {
+        const_value_str.parse::<f64>().ok()?
+    };
+    if float_format == (8, 24) {
+        Some(u64::from((value as f32).to_bits()))
+    } else {
+        Some(value.to_bits())
+    }
+}
+
+pub fn is_slice_pointer_type(var_type: &AnyTypeEnum) -> bool {
+    //! Slices are passed as a pointer to a zero length array of their elements together with their length
+    if let AnyTypeEnum::PointerType(pointer_type) = var_type {
+        matches!(pointer_type.get_element_type(), AnyTypeEnum::ArrayType(array_type) if array_type.len() == 0)
+    } else {
+        false
+    }
+}
+
+pub fn get_aggregate_leaves<'ctx>(var_type: &AnyTypeEnum<'ctx>) -> Vec<(Vec<u32>, AnyTypeEnum<'ctx>)> {
+    //! Flatten a struct or array type into its scalar fields paired with their index paths (a scalar is its own only leaf)
+    match var_type {
+        AnyTypeEnum::StructType(struct_type) => {
+            let mut leaves = Vec::new();
+            for (i, field_type) in struct_type.get_field_types().iter().enumerate() {
+                for (mut path, leaf_type) in get_aggregate_leaves(&field_type.as_any_type_enum()) {
+                    path.insert(0, u32::try_from(i).unwrap());
+                    leaves.push((path, leaf_type));
+                }
+            }
+            leaves
+        }
+        AnyTypeEnum::ArrayType(array_type) => {
+            let element_leaves = get_aggregate_leaves(&array_type.get_element_type().as_any_type_enum());
+            let mut leaves = Vec::new();
+            for i in 0..array_type.len() {
+                for (path, leaf_type) in &element_leaves {
+                    let mut path = path.clone();
+                    path.insert(0, i);
+                    leaves.push((path, *leaf_type));
+                }
+            }
+            leaves
+        }
+        _ => vec![(Vec::new(), *var_type)],
+    }
+}
+
+pub fn get_field_var_name(var_name: &str, path: &[u32]) -> String {
+    //! Aggregates are modelled by one variable per scalar field, named by appending the field indices
+    path.iter().fold(String::from(var_name), |name, index| format!("{}.{}", name, index))
+}
+
+pub fn get_assignment<'a>(solver: &'a Solver<'_>, lvalue_name: &str, rvalue_name: &str, var_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<Bool<'a>> {
+    //! Equate two values of the same type field by field, or None if any field has an unsupported type
+    let mut equalities = Vec::<Bool>::new();
+    for (path, leaf_type) in get_aggregate_leaves(var_type) {
+        let lvalue = get_typed_var(solver, &get_field_var_name(lvalue_name, &path), &leaf_type, encoding)?;
+        let rvalue = get_typed_var(solver, &get_field_var_name(rvalue_name, &path), &leaf_type, encoding)?;
+        equalities.push(lvalue._eq(&rvalue));
+    }
+    let equality_refs: Vec<&Bool> = equalities.iter().collect();
+    Some(Bool::and(solver.get_context(), &equality_refs))
+}
+
+fn get_zero_assignment<'a>(solver: &'a Solver<'_>, var: &Dynamic<'a>, var_type: &AnyTypeEnum) -> Bool<'a> {
+    if let (Some(float_var), Some(float_format)) = (var.as_float(), get_float_format(var_type)) {
+        let zero = if float_format == (8, 24) {
+            Float::from_f32(solver.get_context(), 0.0)
+        } else {
+            Float::from_f64(solver.get_context(), 0.0)
+        };
+        float_var._eq(&zero)
+    } else if let Some(bool_var) = var.as_bool() {
+        bool_var.not()
+    } else if let Some(bv_var) = var.as_bv() {
+        bv_var._eq(&BV::from_u64(solver.get_context(), 0, bv_var.get_size()))
+    } else {
+        var.as_int().unwrap()._eq(&Int::from_i64(solver.get_context(), 0))
+    }
+}
+
+pub fn get_typed_var<'a>(solver: &'a Solver<'_>, var_name: &str, var_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Option<Dynamic<'a>> {
+    //! Create the Z3 variable used to model an LLVM value of the given type (booleans are always modelled as Bool)
+    if var_type.is_pointer_type() {
+        // Pointers are addresses into the memory model in both encodings
+        return Some(Dynamic::from_ast(&Int::new_const(solver.get_context(), var_name)));
+    }
+    if let Some((exponent_width, significand_width)) = get_float_format(var_type) {
+        // Floats follow IEEE-754 in both encodings
+        return Some(Dynamic::from_ast(&Float::new_const(solver.get_context(), var_name, exponent_width, significand_width)));
+    }
+    if !var_type.is_int_type() {
+        return None;
+    }
+    let width = get_int_width(var_type);
+    let var = if width == 1 {
+        Dynamic::from_ast(&Bool::new_const(solver.get_context(), var_name))
+    } else {
+        match encoding {
+            IntegerEncoding::Int => Dynamic::from_ast(&Int::new_const(solver.get_context(), var_name)),
+            IntegerEncoding::BitVector => Dynamic::from_ast(&BV::new_const(solver.get_context(), var_name, width)),
+        }
+    };
+    Some(var)
+}
+
+pub fn get_instruction_string(instruction: &InstructionValue) -> String {
+    instruction.print_to_string().to_string_lossy().trim().to_string()
+}
+
+pub fn get_operand_value<'a>(instruction: &InstructionValue<'a>, index: u32) -> Result<BasicValueEnum<'a>, WombatError> {
+    //! Value operand of an instruction, which a malformed instruction may lack or hold a basic block in instead
+    instruction
+        .get_operand(index)
+        .and_then(|operand| operand.left())
+        .ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(instruction)))
+}
+
+pub fn get_operand_block<'a>(instruction: &InstructionValue<'a>, index: u32) -> Result<BasicBlock<'a>, WombatError> {
+    //! Basic block operand of a terminator
+    instruction
+        .get_operand(index)
+        .and_then(|operand| operand.right())
+        .ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(instruction)))
+}
+
+pub fn get_block_name(instruction: &InstructionValue) -> Result<String, WombatError> {
+    //! Name of the basic block holding the instruction
+    instruction
+        .get_parent()
+        .map(|bb| bb.get_name().to_string_lossy().to_string())
+        .ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(instruction)))
+}
+
+pub fn get_called_function<'a>(module: &InkwellModule<'_>, instruction: &InstructionValue<'a>) -> Result<PointerValue<'a>, WombatError> {
+    //! Callee of a call, its last operand, which must be a function of the module as calls through function pointers are not supported
+    let index = instruction
+        .get_num_operands()
+        .checked_sub(1)
+        .ok_or_else(|| WombatError::MalformedInstruction(get_instruction_string(instruction)))?;
+    match get_operand_value(instruction, index)? {
+        BasicValueEnum::PointerValue(callee)
+            if module
+                .get_function(&callee.get_name().to_string_lossy())
+                .map_or(false, |function| function.as_global_value().as_pointer_value() == callee) =>
+        {
+            Ok(callee)
+        }
+        _ => Err(WombatError::UnsupportedCall(get_instruction_string(instruction))),
+    }
+}
+
+pub fn get_checked_typed_var<'a>(solver: &'a Solver<'_>, var_name: &str, var_type: &AnyTypeEnum, encoding: IntegerEncoding) -> Result<Dynamic<'a>, WombatError> {
+    //! Typed variable for a value the instruction cannot do without
+    get_typed_var(solver, var_name, var_type, encoding).ok_or_else(|| WombatError::UnsupportedType(var_type.to_string()))
+}
+
+pub fn get_var_name<'a>(value: &dyn AnyValue, solver: &'a Solver<'_>, namespace: &str, encoding: IntegerEncoding) -> Result<String, WombatError> {
     let value_llvm_str = value.print_to_string();
     let value_str = value_llvm_str.to_str().unwrap();
-    let name = if !value_str.contains('%') {
+    let name = if !value_str.contains('%') && value_str.contains('@') {
+        // Globals (and constant casts of globals) are shared by every function, so they are not namespaced
+        let start_index = value_str.find('@').unwrap();
+        let end_index = value_str[start_index..]
+            .find(|c: char| c == '"' || c == ' ' || c == ',' || c == ')')
+            .unwrap_or_else(|| value_str[start_index..].len())
+            + start_index;
+        String::from(&value_str[start_index..end_index])
+    } else if let (false, Some(float_format)) = (value_str.contains('%'), get_float_format(&value.as_any_value_enum().get_type())) {
+        // Float constants are asserted as SMT-LIB literals of their exact bit pattern
+        let const_type_str = value_str.split_whitespace().next().unwrap();
+        let const_value_str = value_str.split_whitespace().last().unwrap();
+        let var_name = format!("{}{}_{}", CONST_NAMESPACE, const_type_str, const_value_str);
+        if let Some(bits) = get_float_constant_bits(const_value_str, float_format) {
+            solver.from_string(format!(
+                "(declare-fun |{}| () {}) (assert (= |{}| {}))",
+                var_name,
+                get_float_sort_string(float_format),
+                var_name,
+                get_float_bits_term(bits, float_format)
+            ));
+        } else if !const_value_str.eq("undef") && !const_value_str.eq("poison") {
+            warn!("Currently unsupported float constant {:?}, its value is left unconstrained", value_str);
+        }
+        var_name
+    } else if !value_str.contains('%') && !value.as_any_value_enum().get_type().is_int_type() && !value.as_any_value_enum().get_type().is_pointer_type() {
+        // Aggregate constants are named after their whole literal
+        let var_name = format!("{}{}", CONST_NAMESPACE, value_str.replace(' ', ""));
+        if value_str.ends_with("zeroinitializer") {
+            for (path, leaf_type) in get_aggregate_leaves(&value.as_any_value_enum().get_type()) {
+                if let Some(leaf_var) = get_typed_var(solver, &get_field_var_name(&var_name, &path), &leaf_type, encoding) {
+                    solver.assert(&get_zero_assignment(solver, &leaf_var, &leaf_type));
+                }
+            }
+        } else if !value_str.ends_with("undef") && !value_str.ends_with("poison") {
+            warn!("Currently unsupported constant {:?}, its value is left unconstrained", value_str);
+        }
+        var_name
+    } else if !value_str.contains('%') {
         // handle const literals
-        let const_value_str = value_str.split_whitespace().nth(1).unwrap();
-        let var_name_string = format!("{}{}", CONST_NAMESPACE, const_value_str);
+        let const_type_str = value_str.split_whitespace().next().unwrap();
+        let const_value_str = value_str.split_whitespace().last().unwrap();
+        // Undefined values may be anything, so they are left unconstrained
+        let is_undefined = const_value_str.eq("undef") || const_value_str.eq("poison");
+        let var_name_string = if (encoding == IntegerEncoding::BitVector && !const_type_str.eq("i1")) || is_undefined {
+            // Constants of different widths must not share a bitvector variable
+            format!("{}{}_{}", CONST_NAMESPACE, const_type_str, const_value_str)
+        } else {
+            format!("{}{}", CONST_NAMESPACE, const_value_str)
+        };
         let var_name = var_name_string.as_str();
-        if const_value_str.eq("true") {
+        if const_value_str.eq("null") {
+            let null_const = Int::new_const(solver.get_context(), var_name);
+            solver.assert(&null_const._eq(&Int::from_i64(solver.get_context(), 0)));
+        } else if const_value_str.eq("true") {
             let true_const = Bool::new_const(solver.get_context(), var_name);
             solver.assert(&true_const._eq(&Bool::from_bool(solver.get_context(), true)));
         } else if const_value_str.eq("false") {
             let false_const = Bool::new_const(solver.get_context(), var_name);
             solver.assert(&false_const._eq(&Bool::from_bool(solver.get_context(), false)));
-        } else {
-            let parsed_num = const_value_str.parse::<i64>().unwrap();
-            let num_const = Int::new_const(solver.get_context(), var_name);
-            solver.assert(&num_const._eq(&Int::from_i64(solver.get_context(), parsed_num)));
+        } else if !is_undefined {
+            // Parse as i128 so constants wider than 64 bits are preserved
+            let const_value = const_value_str.parse::<i128>().map_err(|_| WombatError::UnsupportedConstant(String::from(value_str)))?;
+            let parsed_num = get_int_from_i128(solver, const_value);
+            match encoding {
+                IntegerEncoding::Int => {
+                    let num_const = Int::new_const(solver.get_context(), var_name);
+                    solver.assert(&num_const._eq(&parsed_num));
+                }
+                IntegerEncoding::BitVector => {
+                    let width = get_int_width(&value.as_any_value_enum().get_type());
+                    let num_const = BV::new_const(solver.get_context(), var_name, width);
+                    solver.assert(&num_const._eq(&BV::from_int(&parsed_num, width)));
+                }
+            }
         }
         String::from(var_name)
     } else {
@@ -37,17 +327,50 @@ pub fn get_var_name<'a>(value: &dyn AnyValue, solver: &'a Solver<'_>, namespace:
         let var_name = String::from(&value_str[start_index..end_index]);
         format!("{}{}", namespace, var_name)
     };
-    name
-}
-
-pub fn get_min_max_signed_int(size: &str) -> (i64, i64) {
-    match size {
-        "8" => (i8::MIN as i64, i8::MAX as i64),
-        "16" => (i16::MIN as i64, i16::MAX as i64),
-        "32" => (i32::MIN as i64, i32::MAX as i64),
-        "64" => (i64::MIN, i64::MAX),
-        "size" => (isize::MIN as i64, isize::MAX as i64), // isize depends on devices architecture (32 bits or 64 bits)
-        "128" => panic!("i128 are unsupported due to Z3 allowing up to 64 bit integers"),
-        _ => panic!("Unsupported signed integer type"),
+    Ok(name)
+}
+
+pub fn get_int_from_i128<'a>(solver: &'a Solver<'_>, value: i128) -> Int<'a> {
+    //! Z3 only builds numerals up to 64 bits natively, so wider values are created from their decimal string
+    Int::from_str(solver.get_context(), &value.to_string()).unwrap()
+}
+
+pub fn get_int_from_u128<'a>(solver: &'a Solver<'_>, value: u128) -> Int<'a> {
+    Int::from_str(solver.get_context(), &value.to_string()).unwrap()
+}
+
+fn get_int_modulus<'a>(solver: &'a Solver<'_>, width: u32) -> Int<'a> {
+    //! Returns 2^width, the number of distinct values of an integer of the given width
+    let max_unsigned = get_int_from_u128(solver, u128::MAX >> (128 - width));
+    Int::add(solver.get_context(), &[&max_unsigned, &Int::from_u64(solver.get_context(), 1)])
+}
+
+pub fn get_unsigned_int<'a>(solver: &'a Solver<'_>, signed_var: &Int<'a>, width: u32) -> Int<'a> {
+    //! Reinterpret the two's complement value of an Int as unsigned
+    let const_0 = Int::from_i64(solver.get_context(), 0);
+    let wrapped_var = Int::add(solver.get_context(), &[signed_var, &get_int_modulus(solver, width)]);
+    signed_var.lt(&const_0).ite(&wrapped_var, signed_var)
+}
+
+pub fn get_wrapped_signed_int<'a>(solver: &'a Solver<'_>, var: &Int<'a>, width: u32) -> Int<'a> {
+    //! Truncate an arbitrary Int to the given width and return its two's complement value
+    let half_modulus = get_int_modulus(solver, width - 1);
+    let shifted_var = Int::add(solver.get_context(), &[var, &half_modulus]);
+    Int::sub(solver.get_context(), &[&shifted_var.modulo(&get_int_modulus(solver, width)), &half_modulus])
+}
+
+pub fn get_min_max_unsigned_int(width: u32) -> Result<(u128, u128), WombatError> {
+    //! Range of an unsigned integer of any width up to 128 bits
+    match width {
+        1..=128 => Ok((0, u128::MAX >> (128 - width))),
+        _ => Err(WombatError::UnsupportedIntegerType(width.to_string())),
+    }
+}
+
+pub fn get_min_max_signed_int(width: u32) -> Result<(i128, i128), WombatError> {
+    //! Range of a two's complement integer of any width up to 128 bits, where the shifts keep the sign
+    match width {
+        1..=128 => Ok((i128::MIN >> (128 - width), i128::MAX >> (128 - width))),
+        _ => Err(WombatError::UnsupportedIntegerType(width.to_string())),
     }
 }
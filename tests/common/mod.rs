@@ -3,10 +3,15 @@
 
 use std::fs;
 use std::path::Path;
+use std::process::Command;
 
 use tracing_core::Level;
 use tracing_subscriber::FmtSubscriber;
 
+use wombat_symx::config::AnalysisConfig;
+use wombat_symx::error::WombatError;
+use wombat_symx::report::{AnalysisResult, Report};
+
 struct FileDropper<'a> {
     file_name: &'a String,
 }
@@ -18,33 +23,85 @@ impl Drop for FileDropper<'_> {
 }
 
 pub fn test(test_name: &str, function_name: &str, source_code: &str, expected_safe: bool) {
-    // Needs to be manually changed due to the test harness not handling cmd args
-    let debug = false;
+    test_with_config(test_name, function_name, source_code, expected_safe, &AnalysisConfig::new());
+}
 
-    // Setup the tracing debug level
-    let subscriber = if debug {
-        FmtSubscriber::builder().with_max_level(Level::DEBUG).finish()
-    } else {
-        FmtSubscriber::builder().with_max_level(Level::WARN).finish()
-    };
+pub fn test_with_config(test_name: &str, function_name: &str, source_code: &str, expected_safe: bool, config: &AnalysisConfig) {
+    let actual_safe = analyze(test_name, function_name, source_code, config);
 
-    // _guard resets the current default dispatcher to the prior default when dropped
-    let _guard = tracing::subscriber::set_default(subscriber);
+    assert!(expected_safe == actual_safe.unwrap());
+}
 
+fn write_test_source(test_name: &str, function_name: &str, source_code: &str) -> String {
     let source_file_name = format!("tests_temp/zzz_temp_test_{}.rs", test_name);
     if !Path::exists(Path::new("tests_temp")) {
         // Ensure temp test directory exists, otherwise src file fails to be created
         fs::create_dir("tests_temp").expect("Unable to create test directory: \"tests_temp\"");
     }
 
-    let _file_dropper = FileDropper { file_name: &source_file_name };
-
     // Prevent compiler from optimizing away unused function
     let main = format!("fn main() {{println!(\"{{:p}}\", {} as *const ())}}", function_name);
 
     fs::write(&source_file_name, format!("{}\n{}", source_code.replace("            ", ""), main)).expect("Failed to write temp test file!");
+    source_file_name
+}
 
-    let actual_safe = wombat_symx::symbolic_execution::symbolic_execution(&source_file_name, &String::from(function_name), false);
+pub fn analyze_result(test_name: &str, function_name: &str, source_code: &str, config: &AnalysisConfig) -> Result<AnalysisResult, WombatError> {
+    let source_file_name = write_test_source(test_name, function_name, source_code);
+    let _file_dropper = FileDropper { file_name: &source_file_name };
 
-    assert!(expected_safe == actual_safe.unwrap());
+    wombat_symx::symbolic_execution::analyze(&source_file_name, &String::from(function_name), config)
+}
+
+pub fn get_report(test_name: &str, function_name: &str, source_code: &str, config: &AnalysisConfig) -> Result<Report, WombatError> {
+    let source_file_name = write_test_source(test_name, function_name, source_code);
+    let _file_dropper = FileDropper { file_name: &source_file_name };
+
+    wombat_symx::symbolic_execution::get_report(&source_file_name, &String::from(function_name), config)
+}
+
+pub fn analyze_bytecode_result(test_name: &str, function_name: &str, source_code: &str, extension: &str, config: &AnalysisConfig) -> Result<AnalysisResult, WombatError> {
+    //! Analyse the bitcode (bc) or textual IR (ll) emitted for the source, without the source itself
+    let source_file_name = write_test_source(test_name, function_name, source_code);
+    let _file_dropper = FileDropper { file_name: &source_file_name };
+
+    let bytecode_file_name = format!("{}.{}", &source_file_name[0..source_file_name.rfind('.').unwrap()], extension);
+    let emit = if extension == "ll" { "--emit=llvm-ir" } else { "--emit=llvm-bc" };
+    let status = Command::new("rustc").args([emit, &source_file_name, "-o", &bytecode_file_name]).status().expect("Failed to run rustc!");
+    assert!(status.success(), "Failed to generate bytecode file!");
+    let _bytecode_file_dropper = FileDropper { file_name: &bytecode_file_name };
+
+    wombat_symx::symbolic_execution::analyze(&bytecode_file_name, &String::from(function_name), config)
+}
+
+pub fn analyze_ir_result(test_name: &str, function_name: &str, ir: &str, config: &AnalysisConfig) -> Result<AnalysisResult, WombatError> {
+    //! Analyse hand-written textual IR, such as a reproducer that rustc would not emit
+    if !Path::exists(Path::new("tests_temp")) {
+        fs::create_dir("tests_temp").expect("Unable to create test directory: \"tests_temp\"");
+    }
+    let ir_file_name = format!("tests_temp/zzz_temp_test_{}.ll", test_name);
+    fs::write(&ir_file_name, ir.replace("            ", "")).expect("Failed to write temp test file!");
+    let _file_dropper = FileDropper { file_name: &ir_file_name };
+
+    wombat_symx::symbolic_execution::analyze(&ir_file_name, &String::from(function_name), config)
+}
+
+pub fn analyze(test_name: &str, function_name: &str, source_code: &str, config: &AnalysisConfig) -> Option<bool> {
+    // Needs to be manually changed due to the test harness not handling cmd args
+    let debug = false;
+
+    // Setup the tracing debug level
+    let subscriber = if debug {
+        FmtSubscriber::builder().with_max_level(Level::DEBUG).finish()
+    } else {
+        FmtSubscriber::builder().with_max_level(Level::WARN).finish()
+    };
+
+    // _guard resets the current default dispatcher to the prior default when dropped
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let source_file_name = write_test_source(test_name, function_name, source_code);
+    let _file_dropper = FileDropper { file_name: &source_file_name };
+
+    wombat_symx::symbolic_execution::symbolic_execution(&source_file_name, &String::from(function_name), config)
 }
@@ -3,6 +3,14 @@
 
 mod common;
 
+use wombat_symx::codegen::codegen_call::DEFAULT_RECURSION_BOUND;
+use wombat_symx::config::{AnalysisConfig, InputKind};
+use wombat_symx::error::WombatError;
+use wombat_symx::report::{AnalysisResult, ArgumentReport, Counterexample, PanicSiteStatus, Report, Timing, UnknownReason, Verdict, REPORT_SCHEMA_VERSION, SARIF_UNKNOWN_PANIC_RULE_ID};
+use wombat_symx::utils::panic_utils::{PanicKind, PanicSite, SourceLocation};
+use wombat_symx::utils::unroll_loops::is_invariant_function_name;
+use wombat_symx::utils::var_utils::{get_min_max_signed_int, get_min_max_unsigned_int, IntegerEncoding};
+
 #[test]
 fn test_unsafe_abs() {
     common::test(
@@ -551,3 +559,1856 @@ fn test_safe_bool_params() {
         true,
     );
 }
+
+#[test]
+fn test_unsafe_abs_bv() {
+    common::test_with_config(
+        "test_unsafe_abs_bv",
+        "test_unsafe_abs_bv",
+        "
+            fn test_unsafe_abs_bv(mut x: i32) -> i32 {
+                if x < 0 {
+                    x = -1 * x;
+                }
+
+                assert!(x >= 0);
+                x
+            }
+        ",
+        false,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_safe_neg_abs_i8_bv() {
+    common::test_with_config(
+        "test_safe_neg_abs_i8_bv",
+        "test_safe_neg_abs_i8_bv",
+        "
+            fn test_safe_neg_abs_i8_bv(mut x: i8) -> i8 {
+                if x > 0 {
+                    x = -1 * x;
+                }
+
+                assert!(x <= 0);
+                x
+            }
+        ",
+        true,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_unsafe_tricky_phi_bv() {
+    common::test_with_config(
+        "test_unsafe_tricky_phi_bv",
+        "test_unsafe_tricky_phi_bv",
+        "
+            fn test_unsafe_tricky_phi_bv(
+                c1: bool,
+                c2: bool
+            ) -> i32 {
+                let mut r = 0;
+                if c1 {
+                    r -= 1;
+                }
+                if c2 {
+                    r += 2;
+                }
+                assert!(r >= 0);
+                return r;
+            }
+        ",
+        false,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_unsafe_unsigned_sub() {
+    common::test(
+        "test_unsafe_unsigned_sub",
+        "test_unsafe_unsigned_sub",
+        "
+            fn test_unsafe_unsigned_sub(x: u32, y: u32) -> u32 {
+                x - y
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_unsigned_add() {
+    common::test(
+        "test_safe_unsigned_add",
+        "test_safe_unsigned_add",
+        "
+            fn test_safe_unsigned_add(x: u8) -> u16 {
+                let y = x as u16 + 1;
+                assert!(y > 0);
+                y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_unsigned_cmp() {
+    common::test(
+        "test_unsafe_unsigned_cmp",
+        "test_unsafe_unsigned_cmp",
+        "
+            fn test_unsafe_unsigned_cmp(x: u32) -> () {
+                assert!(x <= 2147483647);
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_unsigned_mul_bv() {
+    common::test_with_config(
+        "test_unsafe_unsigned_mul_bv",
+        "test_unsafe_unsigned_mul_bv",
+        "
+            fn test_unsafe_unsigned_mul_bv(x: u64) -> u64 {
+                x * 2
+            }
+        ",
+        false,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_unsafe_abs_i128() {
+    common::test(
+        "test_unsafe_abs_i128",
+        "test_unsafe_abs_i128",
+        "
+            fn test_unsafe_abs_i128(mut x: i128) -> i128 {
+                if x < 0 {
+                    x = -1 * x;
+                }
+
+                assert!(x >= 0);
+                x
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_u128_const() {
+    common::test(
+        "test_safe_u128_const",
+        "test_safe_u128_const",
+        "
+            fn test_safe_u128_const(x: u64) -> u128 {
+                let y = x as u128 + 340282366920938463444927863358058659840;
+                assert!(y >= 340282366920938463444927863358058659840);
+                y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_u128_add_bv() {
+    common::test_with_config(
+        "test_unsafe_u128_add_bv",
+        "test_unsafe_u128_add_bv",
+        "
+            fn test_unsafe_u128_add_bv(x: u128) -> u128 {
+                x + 1
+            }
+        ",
+        false,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_safe_while_loop() {
+    common::test(
+        "test_safe_while_loop",
+        "test_safe_while_loop",
+        "
+            fn test_safe_while_loop(x: bool) -> i32 {
+                let mut i = 0;
+                let mut r = 0;
+                while i < 3 {
+                    if x {
+                        r += 2;
+                    }
+                    i += 1;
+                }
+                assert!(r <= 6);
+                r
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_while_loop() {
+    common::test(
+        "test_unsafe_while_loop",
+        "test_unsafe_while_loop",
+        "
+            fn test_unsafe_while_loop(x: bool) -> i32 {
+                let mut i = 0;
+                let mut r = 0;
+                while i < 2 {
+                    if x {
+                        r += 1;
+                    }
+                    i += 1;
+                }
+                assert!(r < 2);
+                r
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_bound_exhausted_while_loop() {
+    let actual_safe = common::analyze(
+        "test_bound_exhausted_while_loop",
+        "test_bound_exhausted_while_loop",
+        "
+            fn test_bound_exhausted_while_loop(n: i32) -> i32 {
+                let mut i = 0;
+                while i < n {
+                    i += 1;
+                }
+                i
+            }
+        ",
+        &AnalysisConfig::new().unroll_bound(3),
+    );
+
+    assert!(actual_safe.is_none());
+}
+
+#[test]
+fn test_safe_loop_invariant() {
+    common::test(
+        "test_safe_loop_invariant",
+        "test_safe_loop_invariant",
+        "
+            mod wombat_symx {
+                pub fn invariant(_condition: bool) {}
+            }
+
+            fn test_safe_loop_invariant(n: i32) -> i32 {
+                if n < 0 {
+                    return 0;
+                }
+                let mut i = 0;
+                loop {
+                    wombat_symx::invariant(i >= 0 && i <= n);
+                    if i >= n {
+                        break;
+                    }
+                    i += 1;
+                }
+                assert!(i == n);
+                i
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_safe_while_loop_invariant() {
+    // The loop condition runs before the invariant, so the arbitrary iteration must not exit the loop from there
+    common::test(
+        "test_safe_while_loop_invariant",
+        "test_safe_while_loop_invariant",
+        "
+            mod wombat_symx {
+                pub fn invariant(_condition: bool) {}
+            }
+
+            fn test_safe_while_loop_invariant(n: i32) -> i32 {
+                if n < 0 {
+                    return 0;
+                }
+                let mut i = 0;
+                while i < n {
+                    wombat_symx::invariant(i <= n);
+                    i += 1;
+                }
+                assert!(i == n);
+                i
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_loop_invariant_not_inductive() {
+    common::test(
+        "test_unsafe_loop_invariant_not_inductive",
+        "test_unsafe_loop_invariant_not_inductive",
+        "
+            mod wombat_symx {
+                pub fn invariant(_condition: bool) {}
+            }
+
+            fn test_unsafe_loop_invariant_not_inductive(n: i32) -> i32 {
+                let mut i = 0;
+                while i < n {
+                    wombat_symx::invariant(i < 5);
+                    i += 1;
+                }
+                i
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_loop_invariant_memory_write() {
+    // The second iteration writes a[0] which only a later iteration reads, so the panic is missed
+    // if the arbitrary iteration assumes the slice still holds the values it had after the first iteration
+    let result = common::analyze_result(
+        "test_unsafe_loop_invariant_memory_write",
+        "test_unsafe_loop_invariant_memory_write",
+        "
+            mod wombat_symx {
+                pub fn invariant(_condition: bool) {}
+            }
+
+            fn test_unsafe_loop_invariant_memory_write(a: &mut [i32]) -> i32 {
+                if a.is_empty() {
+                    return 0;
+                }
+                a[0] = 0;
+                let mut i = 0;
+                while i < a.len() {
+                    wombat_symx::invariant(i <= a.len());
+                    if i == 1 {
+                        a[0] = 1;
+                    }
+                    if i >= 2 && a[0] == 1 {
+                        panic!();
+                    }
+                    i += 1;
+                }
+                a[0]
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::Explicit);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_invariant_function_name() {
+    assert!(is_invariant_function_name("wombat_symx::invariant"));
+    assert!(is_invariant_function_name("wombat_symx::invariant::h0123456789abcdef"));
+    assert!(is_invariant_function_name("my_crate::wombat_symx::invariant::h0123456789abcdef"));
+    assert!(!is_invariant_function_name("my_crate::not_wombat_symx::invariant::h0123456789abcdef"));
+    assert!(!is_invariant_function_name("wombat_symx::invariant_holds::h0123456789abcdef"));
+}
+
+#[test]
+fn test_safe_bounded_recursion() {
+    common::test(
+        "test_safe_bounded_recursion",
+        "test_safe_bounded_recursion",
+        "
+            fn sum_to(n: i32) -> i32 {
+                if n <= 0 {
+                    return 0;
+                }
+                n + sum_to(n - 1)
+            }
+            fn test_safe_bounded_recursion() -> () {
+                let r = sum_to(3);
+                assert!(r == 6);
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_bounded_recursion() {
+    common::test(
+        "test_unsafe_bounded_recursion",
+        "test_unsafe_bounded_recursion",
+        "
+            fn sum_to(n: i32) -> i32 {
+                if n <= 0 {
+                    return 0;
+                }
+                n + sum_to(n - 1)
+            }
+            fn test_unsafe_bounded_recursion() -> () {
+                let r = sum_to(2);
+                assert!(r == 4);
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_recursion_bound_exhausted() {
+    let actual_safe = common::analyze(
+        "test_recursion_bound_exhausted",
+        "test_recursion_bound_exhausted",
+        "
+            fn gcd(a: u32, b: u32) -> u32 {
+                if b == 0 {
+                    return a;
+                }
+                gcd(b, a % b)
+            }
+            fn test_recursion_bound_exhausted(a: u32, b: u32) -> u32 {
+                gcd(a, b)
+            }
+        ",
+        &AnalysisConfig::new().recursion_bound(2),
+    );
+
+    assert!(actual_safe.is_none());
+}
+
+#[test]
+fn test_safe_func_call_summaries() {
+    let actual_safe = common::analyze(
+        "test_safe_func_call_summaries",
+        "test_safe_func_call_summaries",
+        "
+            fn abs(x: i32) -> i32 {
+                if x < 0 {
+                    return -x;
+                }
+                x
+            }
+            fn test_safe_func_call_summaries(x: i8) -> () {
+                let y = abs(x as i32);
+                let z = abs(-13);
+                assert!(y >= 0 && z == 13);
+            }
+        ",
+        &AnalysisConfig::new().use_summaries(true),
+    );
+
+    assert!(actual_safe.unwrap());
+}
+
+#[test]
+fn test_unsafe_func_call_summaries() {
+    let actual_safe = common::analyze(
+        "test_unsafe_func_call_summaries",
+        "test_unsafe_func_call_summaries",
+        "
+            fn abs(x: i32) -> i32 {
+                if x < 0 {
+                    return -x;
+                }
+                x
+            }
+            fn test_unsafe_func_call_summaries(x: i32) -> () {
+                let y = abs(x);
+                let z = abs(y - 1);
+                assert!(z >= 0);
+            }
+        ",
+        &AnalysisConfig::new().use_summaries(true),
+    );
+
+    assert!(!actual_safe.unwrap());
+}
+
+#[test]
+fn test_safe_func_call_summaries_local_memory() {
+    // Each call to the summarised function allocates its array in a region of its own
+    let actual_safe = common::analyze(
+        "test_safe_func_call_summaries_local_memory",
+        "test_safe_func_call_summaries_local_memory",
+        "
+            fn pick(x: i8, i: usize) -> i8 {
+                let values = [x, 7];
+                values[i % 2]
+            }
+            fn test_safe_func_call_summaries_local_memory(x: i8) -> () {
+                let y = pick(x, 0);
+                let z = pick(x, 1);
+                assert!(y == x && z == 7);
+            }
+        ",
+        &AnalysisConfig::new().use_summaries(true),
+    );
+
+    assert!(actual_safe.unwrap());
+}
+
+#[test]
+fn test_safe_array_store_load() {
+    common::test(
+        "test_safe_array_store_load",
+        "test_safe_array_store_load",
+        "
+            fn test_safe_array_store_load(x: i32) -> () {
+                let mut a = [0, 0, 0];
+                a[0] = x;
+                a[1] = 1;
+                assert!(a[0] == x && a[2] == 0);
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_array_store_load() {
+    common::test(
+        "test_unsafe_array_store_load",
+        "test_unsafe_array_store_load",
+        "
+            fn test_unsafe_array_store_load(x: i32) -> () {
+                let mut a = [0, 0, 0];
+                a[0] = x;
+                a[1] = 1;
+                assert!(a[0] != 7);
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_mut_reference_out_parameter() {
+    common::test(
+        "test_safe_mut_reference_out_parameter",
+        "test_safe_mut_reference_out_parameter",
+        "
+            fn set(x: &mut i32, value: i32) -> () {
+                *x = value;
+            }
+            fn test_safe_mut_reference_out_parameter(a: i32) -> () {
+                let mut y = a;
+                set(&mut y, 5);
+                set(&mut y, 6);
+                assert!(y == 6);
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_mut_reference_parameter() {
+    common::test(
+        "test_unsafe_mut_reference_parameter",
+        "test_unsafe_mut_reference_parameter",
+        "
+            fn test_unsafe_mut_reference_parameter(x: &mut i32) -> () {
+                *x += 1;
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_narrowing_pointer_load() {
+    // The i32 load reads the cell holding the whole i64, which must be truncated rather than rule the path out
+    let result = common::analyze_result(
+        "test_unsafe_narrowing_pointer_load",
+        "test_unsafe_narrowing_pointer_load",
+        "
+            fn test_unsafe_narrowing_pointer_load(x: i64) -> i32 {
+                let low = unsafe { *(&x as *const i64 as *const i32) };
+                if x > i32::MAX as i64 && low == 0 {
+                    panic!();
+                }
+                low
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.arguments[0].value.parse::<i64>().unwrap() % (1 << 32), 0);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_unsafe_slice_index() {
+    common::test(
+        "test_unsafe_slice_index",
+        "test_unsafe_slice_index",
+        "
+            fn test_unsafe_slice_index(a: &[i32], i: usize) -> i32 {
+                a[i]
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_mut_slice_index() {
+    common::test(
+        "test_unsafe_mut_slice_index",
+        "test_unsafe_mut_slice_index",
+        "
+            fn test_unsafe_mut_slice_index(a: &mut [u8], i: usize) -> () {
+                a[i] = 1;
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_array_index() {
+    common::test(
+        "test_safe_array_index",
+        "test_safe_array_index",
+        "
+            fn test_safe_array_index(a: [i32; 4], i: usize) -> i32 {
+                if i < 4 {
+                    return a[i];
+                }
+                0
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_array_index() {
+    common::test(
+        "test_unsafe_array_index",
+        "test_unsafe_array_index",
+        "
+            fn test_unsafe_array_index(a: [i32; 4], i: usize) -> i32 {
+                if i <= 4 {
+                    return a[i];
+                }
+                0
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_struct_parameter() {
+    common::test(
+        "test_safe_struct_parameter",
+        "test_safe_struct_parameter",
+        "
+            struct Point {
+                x: i32,
+                y: i32,
+            }
+
+            fn test_safe_struct_parameter(p: Point) -> i32 {
+                if p.x > 0 && p.x < 100 && p.y > 0 && p.y < 100 {
+                    return p.x + p.y;
+                }
+                0
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_struct_parameter() {
+    common::test(
+        "test_unsafe_struct_parameter",
+        "test_unsafe_struct_parameter",
+        "
+            struct Point {
+                x: i32,
+                y: i32,
+                z: i64,
+            }
+
+            fn test_unsafe_struct_parameter(p: Point) -> i32 {
+                if p.z == 7 {
+                    return p.x + p.y;
+                }
+                0
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_tuple_parameter() {
+    common::test(
+        "test_unsafe_tuple_parameter",
+        "test_unsafe_tuple_parameter",
+        "
+            fn test_unsafe_tuple_parameter(t: (i32, bool)) -> i32 {
+                if t.1 {
+                    return t.0 * 2;
+                }
+                t.0
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_tuple_return() {
+    common::test(
+        "test_safe_tuple_return",
+        "test_safe_tuple_return",
+        "
+            fn swap(a: i32, b: i32) -> (i32, i32) {
+                (b, a)
+            }
+
+            fn test_safe_tuple_return(a: i32, b: i32) -> i32 {
+                let (c, d) = swap(a, b);
+                if c != b || d != a {
+                    panic!();
+                }
+                c
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_safe_float_comparison() {
+    common::test(
+        "test_safe_float_comparison",
+        "test_safe_float_comparison",
+        "
+            fn test_safe_float_comparison(x: f32) -> i32 {
+                if x > 1.0 && x * 2.0 <= 2.0 {
+                    panic!();
+                }
+                1
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_float_nan() {
+    common::test(
+        "test_unsafe_float_nan",
+        "test_unsafe_float_nan",
+        "
+            fn test_unsafe_float_nan(x: f64) -> i32 {
+                if x == x {
+                    return 0;
+                }
+                panic!();
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_float_rounding() {
+    common::test(
+        "test_unsafe_float_rounding",
+        "test_unsafe_float_rounding",
+        "
+            fn test_unsafe_float_rounding(x: f32) -> i32 {
+                if x + 1.0 == x && x < f32::INFINITY {
+                    panic!();
+                }
+                0
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_float_to_int_cast() {
+    common::test(
+        "test_safe_float_to_int_cast",
+        "test_safe_float_to_int_cast",
+        "
+            fn test_safe_float_to_int_cast(x: f64) -> i32 {
+                let y = x as i32;
+                if y == i32::MIN && x > 0.0 {
+                    panic!();
+                }
+                y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_option_unwrap() {
+    common::test(
+        "test_unsafe_option_unwrap",
+        "test_unsafe_option_unwrap",
+        "
+            fn test_unsafe_option_unwrap(x: Option<i32>) -> i32 {
+                x.unwrap()
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_option_match() {
+    common::test(
+        "test_safe_option_match",
+        "test_safe_option_match",
+        "
+            fn test_safe_option_match(x: Option<i32>) -> i32 {
+                match x {
+                    Some(y) if y > 0 => y - 1,
+                    Some(_) => 0,
+                    None => -1,
+                }
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_result_parameter() {
+    common::test(
+        "test_unsafe_result_parameter",
+        "test_unsafe_result_parameter",
+        "
+            fn test_unsafe_result_parameter(x: Result<i32, i32>) -> i32 {
+                match x {
+                    Ok(y) => y,
+                    Err(e) if e < 0 => panic!(),
+                    Err(_) => 0,
+                }
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_fieldless_enum_match() {
+    common::test(
+        "test_safe_fieldless_enum_match",
+        "test_safe_fieldless_enum_match",
+        "
+            enum Direction {
+                North,
+                East,
+                South = 4,
+            }
+
+            fn test_safe_fieldless_enum_match(x: Direction) -> i32 {
+                match x {
+                    Direction::North => 0,
+                    Direction::East => 1,
+                    Direction::South => 2,
+                }
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_enum_variant_field() {
+    common::test(
+        "test_unsafe_enum_variant_field",
+        "test_unsafe_enum_variant_field",
+        "
+            enum Shape {
+                Empty,
+                Square(i32),
+                Rectangle { width: i32, height: i64 },
+            }
+
+            fn test_unsafe_enum_variant_field(x: Shape) -> i64 {
+                match x {
+                    Shape::Empty => 0,
+                    Shape::Square(side) => side as i64,
+                    Shape::Rectangle { width, height } => {
+                        if width == 3 && height == 7 {
+                            panic!();
+                        }
+                        height
+                    }
+                }
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_unsafe_bitwise_mask() {
+    common::test(
+        "test_unsafe_bitwise_mask",
+        "test_unsafe_bitwise_mask",
+        "
+            fn test_unsafe_bitwise_mask(x: i32) -> i32 {
+                if ((x & 0xF0) | 0x01) ^ 0x10 == 0x21 {
+                    panic!();
+                }
+                x
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_logical_shift_right() {
+    common::test(
+        "test_safe_logical_shift_right",
+        "test_safe_logical_shift_right",
+        "
+            fn test_safe_logical_shift_right(x: u8) -> u8 {
+                let y = x >> 4;
+                if y > 15 {
+                    panic!();
+                }
+                y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_sign_extension() {
+    common::test(
+        "test_unsafe_sign_extension",
+        "test_unsafe_sign_extension",
+        "
+            fn test_unsafe_sign_extension(x: i8) -> i64 {
+                let y = x as i64;
+                if y < -100 {
+                    panic!();
+                }
+                y
+            }
+        ",
+        false,
+    );
+}
+
+#[test]
+fn test_safe_truncation() {
+    common::test(
+        "test_safe_truncation",
+        "test_safe_truncation",
+        "
+            fn test_safe_truncation(x: i32) -> u8 {
+                let y = x as u8;
+                if y as i32 > 255 || (x == 257 && y != 1) {
+                    panic!();
+                }
+                y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_safe_signed_remainder_sign() {
+    common::test(
+        "test_safe_signed_remainder_sign",
+        "test_safe_signed_remainder_sign",
+        "
+            fn test_safe_signed_remainder_sign(x: i32) -> i32 {
+                if x < 0 && (x % 2 > 0 || x / 2 < x) {
+                    panic!();
+                }
+                x
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_safe_signed_remainder_sign_bv() {
+    common::test_with_config(
+        "test_safe_signed_remainder_sign_bv",
+        "test_safe_signed_remainder_sign_bv",
+        "
+            fn test_safe_signed_remainder_sign_bv(x: i32) -> i32 {
+                if x < 0 && (x % 2 > 0 || x / 2 < x) {
+                    panic!();
+                }
+                x
+            }
+        ",
+        true,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_unsafe_division_by_zero() {
+    // Both the division by zero and the division overflow are reachable, so each site is checked separately
+    let report = common::get_report(
+        "test_unsafe_division_by_zero",
+        "test_unsafe_division_by_zero",
+        "
+            fn test_unsafe_division_by_zero(x: i32, y: i32) -> i32 {
+                if y < 5 {
+                    return x / y;
+                }
+                0
+            }
+        ",
+        &AnalysisConfig::new().enumerate_panics(true).replay(false),
+    )
+    .unwrap();
+
+    assert_eq!(report.verdict, Verdict::Unsafe);
+    let panic_sites = report.panic_sites.unwrap();
+    let panic_site = panic_sites.iter().find(|panic_site| panic_site.panic.kind == PanicKind::DivisionByZero).unwrap();
+    assert_eq!(panic_site.status, PanicSiteStatus::Reachable);
+    let counterexample = &panic_site.counterexamples[0];
+    assert_eq!(counterexample.arguments[1].value, "0");
+    assert_eq!(counterexample.detail.as_deref(), Some("Division by zero: the divisor is 0"));
+}
+
+#[test]
+fn test_unsafe_division_overflow() {
+    let result = common::analyze_result(
+        "test_unsafe_division_overflow",
+        "test_unsafe_division_overflow",
+        "
+            fn test_unsafe_division_overflow(x: i32, y: i32) -> i32 {
+                if y != 0 {
+                    return x / y;
+                }
+                0
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::DivisionOverflow);
+            assert_eq!(counterexample.arguments[0].value, "-2147483648");
+            assert_eq!(counterexample.arguments[1].value, "-1");
+            assert_eq!(counterexample.detail.as_deref(), Some("Division overflow: the dividend is -2147483648 and the divisor is -1"));
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_unsafe_division_overflow_i128() {
+    let result = common::analyze_result(
+        "test_unsafe_division_overflow_i128",
+        "test_unsafe_division_overflow_i128",
+        "
+            fn test_unsafe_division_overflow_i128(x: i128, y: i128) -> i128 {
+                if y != 0 {
+                    return x / y;
+                }
+                0
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::DivisionOverflow);
+            assert_eq!(
+                counterexample.detail.as_deref(),
+                Some("Division overflow: the dividend is -170141183460469231731687303715884105728 and the divisor is -1")
+            );
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_safe_checked_division() {
+    common::test(
+        "test_safe_checked_division",
+        "test_safe_checked_division",
+        "
+            fn test_safe_checked_division(x: i32, y: i32) -> i32 {
+                if y == 0 || (x == i32::MIN && y == -1) {
+                    return 0;
+                }
+                x / y + x % y
+            }
+        ",
+        true,
+    );
+}
+
+#[test]
+fn test_unsafe_unsigned_remainder_by_zero_bv() {
+    common::test_with_config(
+        "test_unsafe_unsigned_remainder_by_zero_bv",
+        "test_unsafe_unsigned_remainder_by_zero_bv",
+        "
+            fn test_unsafe_unsigned_remainder_by_zero_bv(x: u32, y: u32) -> u32 {
+                x % y
+            }
+        ",
+        false,
+        &AnalysisConfig::new().encoding(IntegerEncoding::BitVector),
+    );
+}
+
+#[test]
+fn test_unsafe_assert_eq() {
+    let result = common::analyze_result(
+        "test_unsafe_assert_eq",
+        "test_unsafe_assert_eq",
+        "
+            fn test_unsafe_assert_eq(x: i32) -> i32 {
+                assert_eq!(x % 3, 0);
+                x / 3
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_ne!(counterexample.arguments[0].value.parse::<i32>().unwrap() % 3, 0);
+            let panic = counterexample.panic.unwrap();
+            assert_eq!(panic.kind, PanicKind::Assert);
+            // The operands of `assert_eq!` are formatted at runtime, so there is no constant message
+            assert_eq!(panic.message, None);
+            let location = panic.location.unwrap();
+            assert!(location.file.ends_with("zzz_temp_test_test_unsafe_assert_eq.rs"));
+            assert_eq!(location.line, 3);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_unsafe_unreachable_macro() {
+    let result = common::analyze_result(
+        "test_unsafe_unreachable_macro",
+        "test_unsafe_unreachable_macro",
+        "
+            fn test_unsafe_unreachable_macro(x: u8) -> u8 {
+                match x % 4 {
+                    0 | 1 | 2 => x,
+                    _ => unreachable!(),
+                }
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.arguments[0].value.parse::<u8>().unwrap() % 4, 3);
+            let panic = counterexample.panic.unwrap();
+            assert_eq!(panic.kind, PanicKind::Unreachable);
+            assert_eq!(panic.message.as_deref(), Some("internal error: entered unreachable code"));
+            let location = panic.location.unwrap();
+            assert!(location.file.ends_with("zzz_temp_test_test_unsafe_unreachable_macro.rs"));
+            assert_eq!(location.line, 5);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_unsafe_enumerate_panic_sites() {
+    let report = common::get_report(
+        "test_unsafe_enumerate_panic_sites",
+        "test_unsafe_enumerate_panic_sites",
+        "
+            fn test_unsafe_enumerate_panic_sites(x: i32, y: i32) -> i32 {
+                if x > 10 {
+                    panic!();
+                }
+                if y < 0 && y > 0 {
+                    unreachable!();
+                }
+                x / y
+            }
+        ",
+        &AnalysisConfig::new().enumerate_panics(true).replay(false),
+    )
+    .unwrap();
+
+    assert_eq!(report.verdict, Verdict::Unsafe);
+    let panic_sites = report.panic_sites.unwrap();
+    let reachable_kinds: Vec<PanicKind> = panic_sites
+        .iter()
+        .filter(|panic_site| panic_site.status == PanicSiteStatus::Reachable)
+        .map(|panic_site| panic_site.panic.kind)
+        .collect();
+    let unreachable_kinds: Vec<PanicKind> = panic_sites
+        .iter()
+        .filter(|panic_site| panic_site.status == PanicSiteStatus::Unreachable)
+        .map(|panic_site| panic_site.panic.kind)
+        .collect();
+    assert_eq!(reachable_kinds.len(), 3);
+    for kind in [PanicKind::Explicit, PanicKind::DivisionByZero, PanicKind::DivisionOverflow] {
+        assert!(reachable_kinds.contains(&kind));
+    }
+    assert_eq!(unreachable_kinds, vec![PanicKind::Unreachable]);
+    assert_eq!(panic_sites.len(), 4);
+
+    // Each reachable site comes with a counterexample that reaches that very site
+    for panic_site in panic_sites.iter().filter(|panic_site| panic_site.status == PanicSiteStatus::Reachable) {
+        assert_eq!(panic_site.counterexamples.len(), 1);
+        assert_eq!(panic_site.counterexamples[0].panic.as_ref().unwrap().kind, panic_site.panic.kind);
+    }
+    for panic_site in panic_sites.iter().filter(|panic_site| panic_site.status == PanicSiteStatus::Unreachable) {
+        assert!(panic_site.counterexamples.is_empty());
+    }
+}
+
+fn get_unsafe_report() -> Report {
+    Report {
+        schema_version: REPORT_SCHEMA_VERSION,
+        file_name: String::from("test.rs"),
+        function_name: String::from("test::test_report_json_schema"),
+        verdict: Verdict::Unsafe,
+        counterexamples: vec![Counterexample {
+            arguments: vec![ArgumentReport {
+                name: String::from("x"),
+                type_name: String::from("u8"),
+                value: String::from("0"),
+            }],
+            panic: Some(PanicSite {
+                kind: PanicKind::DivisionByZero,
+                message: None,
+                location: Some(SourceLocation {
+                    file: String::from("test.rs"),
+                    line: 2,
+                    column: 5,
+                }),
+            }),
+            detail: None,
+            replay_error: None,
+        }],
+        panic_sites: None,
+        timing: Timing::default(),
+    }
+}
+
+#[test]
+fn test_report_json_schema() {
+    let json = serde_json::to_value(&get_unsafe_report()).unwrap();
+    assert_eq!(json["verdict"], "unsafe");
+    assert_eq!(json["counterexamples"][0]["arguments"][0]["type"], "u8");
+    assert_eq!(json["counterexamples"][0]["panic"]["kind"], "division_by_zero");
+    assert_eq!(json["counterexamples"][0]["panic"]["location"]["line"], 2);
+    assert!(json["panic_sites"].is_null());
+    assert_eq!(json["timing"]["total_ms"], 0);
+}
+
+#[test]
+fn test_report_json_end_to_end() {
+    let report = common::get_report(
+        "test_report_json_end_to_end",
+        "test_report_json_end_to_end",
+        "
+            fn test_report_json_end_to_end(x: u8, y: u8) -> u8 {
+                x / y
+            }
+        ",
+        &AnalysisConfig::new(),
+    )
+    .unwrap();
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["schema_version"], REPORT_SCHEMA_VERSION);
+    assert_eq!(json["verdict"], "unsafe");
+    assert!(json["function_name"].as_str().unwrap().ends_with("::test_report_json_end_to_end"));
+    let counterexample = &json["counterexamples"][0];
+    assert_eq!(counterexample["arguments"][0]["name"], "x");
+    assert_eq!(counterexample["arguments"][0]["type"], "u8");
+    assert_eq!(counterexample["arguments"][1]["name"], "y");
+    assert_eq!(counterexample["arguments"][1]["type"], "u8");
+    assert_eq!(counterexample["arguments"][1]["value"], "0");
+    assert_eq!(counterexample["panic"]["kind"], "division_by_zero");
+    assert!(counterexample["panic"]["location"]["file"].as_str().unwrap().ends_with("zzz_temp_test_test_report_json_end_to_end.rs"));
+    assert_eq!(counterexample["panic"]["location"]["line"], 3);
+    assert_eq!(counterexample["panic"]["location"]["column"], 5);
+    assert_eq!(counterexample["detail"], "Division by zero: the divisor is 0");
+    assert!(counterexample["replay_error"].as_str().unwrap().contains("attempt to divide by zero"));
+    assert!(json["panic_sites"].is_null());
+}
+
+#[test]
+fn test_report_sarif() {
+    let sarif = get_unsafe_report().get_sarif();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "division_by_zero");
+    assert_eq!(run["tool"]["driver"]["rules"][result["ruleIndex"].as_u64().unwrap() as usize]["id"], "division_by_zero");
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+    assert!(result["message"]["text"].as_str().unwrap().contains("x = 0"));
+}
+
+#[test]
+fn test_report_sarif_unknown_panic() {
+    let mut report = get_unsafe_report();
+    report.counterexamples[0].panic = None;
+    let sarif = report.get_sarif();
+    let run = &sarif["runs"][0];
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], SARIF_UNKNOWN_PANIC_RULE_ID);
+    assert_eq!(run["tool"]["driver"]["rules"][result["ruleIndex"].as_u64().unwrap() as usize]["id"], SARIF_UNKNOWN_PANIC_RULE_ID);
+}
+
+#[test]
+fn test_report_sarif_end_to_end() {
+    let report = common::get_report(
+        "test_report_sarif_end_to_end",
+        "test_report_sarif_end_to_end",
+        "
+            fn test_report_sarif_end_to_end(x: i32) {
+                assert!(x != 42);
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    )
+    .unwrap();
+
+    let sarif = report.get_sarif();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["properties"]["verdict"], "unsafe");
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!(result["ruleId"], "assert");
+    assert_eq!(run["tool"]["driver"]["rules"][result["ruleIndex"].as_u64().unwrap() as usize]["id"], "assert");
+    assert_eq!(result["level"], "error");
+    let physical_location = &result["locations"][0]["physicalLocation"];
+    assert!(physical_location["artifactLocation"]["uri"]
+        .as_str()
+        .unwrap()
+        .ends_with("zzz_temp_test_test_report_sarif_end_to_end.rs"));
+    assert_eq!(physical_location["region"]["startLine"], 3);
+    assert!(result["message"]["text"].as_str().unwrap().contains("x = 42"));
+    assert_eq!(result["properties"]["arguments"][0]["value"], "42");
+}
+
+#[test]
+fn test_analysis_result() {
+    let result = common::analyze_result(
+        "test_analysis_result",
+        "test_analysis_result",
+        "
+            fn test_analysis_result(x: i32, y: i32) -> i32 {
+                x / y
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.arguments.len(), 2);
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::DivisionByZero);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_config() {
+    let config = AnalysisConfig::new().encoding(IntegerEncoding::BitVector).unroll_bound(3).replay(false);
+    assert_eq!(config.encoding, IntegerEncoding::BitVector);
+    assert_eq!(config.unroll_bound, 3);
+    assert_eq!(config.replay, Some(false));
+    assert_eq!(config.input_kind, None);
+    assert_eq!(config.get_input_kind("test.rs"), InputKind::Source);
+    assert_eq!(config.get_input_kind("test.ll"), InputKind::Bytecode);
+    assert_eq!(config.clone().input_kind(InputKind::PrecompiledSource).get_input_kind("test.ll"), InputKind::PrecompiledSource);
+    assert_eq!(AnalysisConfig::new().get_replay(InputKind::Source).ok(), Some(true));
+    assert_eq!(AnalysisConfig::new().get_replay(InputKind::Bytecode).ok(), Some(false));
+    assert!(matches!(AnalysisConfig::new().replay(true).get_replay(InputKind::Bytecode), Err(WombatError::ReplayWithoutSource)));
+    assert_eq!(config.get_unroll_bound().ok(), Some(3));
+    assert!(matches!(AnalysisConfig::new().unroll_bound(0).get_unroll_bound(), Err(WombatError::InvalidUnrollBound)));
+    assert_eq!(config.recursion_bound, DEFAULT_RECURSION_BOUND);
+    assert_eq!(config.counterexample_count, 1);
+    assert_eq!(config.solver_timeout_ms, None);
+}
+
+#[test]
+fn test_analysis_result_without_counterexample() {
+    // The verdict stays unsafe when no counterexample is requested
+    let result = common::analyze_result(
+        "test_analysis_result_without_counterexample",
+        "test_analysis_result_without_counterexample",
+        "
+            fn test_analysis_result_without_counterexample(x: i32, y: i32) -> i32 {
+                x / y
+            }
+        ",
+        &AnalysisConfig::new().counterexample_count(0),
+    );
+
+    assert!(matches!(result.unwrap(), AnalysisResult::Unsafe { counterexample: None }));
+}
+
+#[test]
+fn test_report_distinct_counterexamples() {
+    let report = common::get_report(
+        "test_report_distinct_counterexamples",
+        "test_report_distinct_counterexamples",
+        "
+            fn test_report_distinct_counterexamples(x: i32) -> i32 {
+                if x > 10 && x < 20 {
+                    panic!(\"in range\");
+                }
+                x
+            }
+        ",
+        &AnalysisConfig::new().counterexample_count(3),
+    )
+    .unwrap();
+
+    assert_eq!(report.verdict, Verdict::Unsafe);
+    assert_eq!(report.counterexamples.len(), 3);
+    let values: Vec<i32> = report.counterexamples.iter().map(|counterexample| counterexample.arguments[0].value.parse().unwrap()).collect();
+    for (i, value) in values.iter().enumerate() {
+        assert!(*value > 10 && *value < 20);
+        assert!(!values[..i].contains(value));
+    }
+    for counterexample in &report.counterexamples {
+        assert_eq!(counterexample.panic.as_ref().unwrap().kind, PanicKind::Explicit);
+        assert!(counterexample.replay_error.as_ref().unwrap().contains("in range"));
+    }
+}
+
+#[test]
+fn test_analysis_result_minimal_counterexample() {
+    let result = common::analyze_result(
+        "test_analysis_result_minimal_counterexample",
+        "test_analysis_result_minimal_counterexample",
+        "
+            fn test_analysis_result_minimal_counterexample(x: i32) {
+                assert!(x < 1000);
+            }
+        ",
+        &AnalysisConfig::new().minimize(true),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.arguments[0].value, "1000");
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::Assert);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_invalid_file() {
+    // Benchmark mode skips compilation, so the missing bytecode file is reported
+    let result = wombat_symx::symbolic_execution::analyze(
+        &String::from("tests_temp/zzz_temp_test_missing.rs"),
+        &String::from("missing"),
+        &AnalysisConfig::new().input_kind(InputKind::PrecompiledSource),
+    );
+
+    assert!(matches!(result, Err(WombatError::InvalidFile(_))));
+}
+
+#[test]
+fn test_analysis_result_compilation_failed() {
+    let result = common::analyze_result(
+        "test_analysis_result_compilation_failed",
+        "test_analysis_result_compilation_failed",
+        "
+            fn test_analysis_result_compilation_failed(x: i32) -> i32 {
+                x + true
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    assert!(matches!(result, Err(WombatError::CompilationFailed(_))));
+}
+
+#[test]
+fn test_analysis_result_llvm_ir() {
+    let result = common::analyze_bytecode_result(
+        "test_analysis_result_llvm_ir",
+        "test_analysis_result_llvm_ir",
+        "
+            fn test_analysis_result_llvm_ir(x: i32, y: i32) -> i32 {
+                x / y
+            }
+        ",
+        "ll",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.replay_error, None);
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::DivisionByZero);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_cross_function_call() {
+    // Neither function is named after the module, so the call is only inlined because the callee has a body
+    let result = common::analyze_ir_result(
+        "test_analysis_result_llvm_ir_cross_function_call",
+        "check",
+        "
+            define i32 @helper(i32 %x) {
+            start:
+              %is_large = icmp sgt i32 %x, 100
+              br i1 %is_large, label %panic, label %done
+
+            panic:
+              unreachable
+
+            done:
+              ret i32 %x
+            }
+
+            define i32 @check(i32 %y) {
+            start:
+              %result = call i32 @helper(i32 %y)
+              ret i32 %result
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert!(counterexample.arguments[0].value.parse::<i32>().unwrap() > 100);
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::Unreachable);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_odd_width_parameter() {
+    let result = common::analyze_ir_result(
+        "test_analysis_result_llvm_ir_odd_width_parameter",
+        "check",
+        "
+            define i24 @check(i24 %x) {
+            start:
+              %is_min = icmp eq i24 %x, -8388608
+              br i1 %is_min, label %panic, label %done
+
+            panic:
+              unreachable
+
+            done:
+              ret i24 %x
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            assert_eq!(counterexample.arguments[0].value, "-8388608");
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_irreducible_loop() {
+    // The cycle between first and second is entered through both blocks, so neither is a header to unroll from
+    let result = common::analyze_ir_result(
+        "test_analysis_result_llvm_ir_irreducible_loop",
+        "check",
+        "
+            define i32 @check(i32 %x) {
+            start:
+              %positive = icmp sgt i32 %x, 0
+              br i1 %positive, label %first, label %second
+
+            first:
+              %large = icmp sgt i32 %x, 10
+              br i1 %large, label %second, label %done
+
+            second:
+              %small = icmp slt i32 %x, 100
+              br i1 %small, label %first, label %done
+
+            done:
+              ret i32 %x
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unknown { reason } => assert_eq!(reason, UnknownReason::BoundExhausted),
+        _ => panic!("Expected an unknown result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_indirect_call() {
+    // The callee is a parameter which happens to share its name with a function of the module
+    let result = common::analyze_ir_result(
+        "test_analysis_result_llvm_ir_indirect_call",
+        "check",
+        "
+            define i32 @f(i32 %x) {
+            start:
+              ret i32 %x
+            }
+
+            define i32 @check(i32 (i32)* %f, i32 %x) {
+            start:
+              %result = call i32 %f(i32 %x)
+              ret i32 %result
+            }
+        ",
+        &AnalysisConfig::new(),
+    );
+
+    assert!(matches!(result, Err(WombatError::UnsupportedCall(_))));
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_zero_unroll_bound() {
+    // A bound of 0 is rejected rather than analysed as if it were 1
+    let result = common::analyze_ir_result(
+        "test_analysis_result_llvm_ir_zero_unroll_bound",
+        "check",
+        "
+            define i32 @check(i32 %x) {
+            start:
+              br label %loop
+
+            loop:
+              %is_zero = icmp eq i32 %x, 0
+              br i1 %is_zero, label %done, label %loop
+
+            done:
+              ret i32 %x
+            }
+        ",
+        &AnalysisConfig::new().unroll_bound(0),
+    );
+
+    assert!(matches!(result, Err(WombatError::InvalidUnrollBound)));
+}
+
+#[test]
+fn test_analysis_result_llvm_ir_replay() {
+    let result = common::analyze_bytecode_result(
+        "test_analysis_result_llvm_ir_replay",
+        "test_analysis_result_llvm_ir_replay",
+        "
+            fn test_analysis_result_llvm_ir_replay(x: i32, y: i32) -> i32 {
+                x / y
+            }
+        ",
+        "ll",
+        &AnalysisConfig::new().replay(true),
+    );
+
+    assert!(matches!(result, Err(WombatError::ReplayWithoutSource)));
+}
+
+#[test]
+fn test_analysis_result_bitcode() {
+    let result = common::analyze_bytecode_result(
+        "test_analysis_result_bitcode",
+        "test_analysis_result_bitcode",
+        "
+            fn test_analysis_result_bitcode(x: u8) -> u8 {
+                x + 1
+            }
+        ",
+        "bc",
+        &AnalysisConfig::new(),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            // Without the source, the argument is typed by its LLVM type
+            assert_eq!(counterexample.arguments[0].type_name, "i8");
+            assert_eq!(counterexample.replay_error, None);
+            assert_eq!(counterexample.panic.unwrap().kind, PanicKind::Overflow);
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_parameter_types() {
+    let result = common::analyze_result(
+        "test_analysis_result_parameter_types",
+        "test_analysis_result_parameter_types",
+        "
+            // fn test_analysis_result_parameter_types(x: i8) -> i8
+            fn test_analysis_result_parameter_types(f: fn(i32) -> i32, x: u8) -> u8 {
+                x + 1
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            let argument = counterexample.arguments.iter().find(|argument| argument.name == "x").unwrap();
+            assert_eq!(argument.type_name, "u8");
+            assert_eq!(argument.value, "255");
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_reordered_struct_fields() {
+    let result = common::analyze_result(
+        "test_analysis_result_reordered_struct_fields",
+        "test_analysis_result_reordered_struct_fields",
+        "
+            struct Mixed {
+                a: u8,
+                b: i64,
+                c: u16,
+            }
+
+            fn test_analysis_result_reordered_struct_fields(m: &Mixed) -> i64 {
+                if m.a == 3 && m.b == -7 && m.c == 500 {
+                    panic!();
+                }
+                m.b
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            let argument = counterexample.arguments.iter().find(|argument| argument.name == "m").unwrap();
+            assert_eq!(argument.type_name, "&Mixed");
+            assert!(argument.value.contains("a: 3"));
+            assert!(argument.value.contains("b: -7"));
+            assert!(argument.value.contains("c: 500"));
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_niche_enum_parameter() {
+    let result = common::analyze_result(
+        "test_analysis_result_niche_enum_parameter",
+        "test_analysis_result_niche_enum_parameter",
+        "
+            use std::num::NonZeroU32;
+
+            fn test_analysis_result_niche_enum_parameter(x: Option<NonZeroU32>) -> u32 {
+                x.unwrap().get()
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            let argument = counterexample.arguments.iter().find(|argument| argument.name == "x").unwrap();
+            assert_eq!(argument.value, "None");
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_analysis_result_niche_reference_parameter() {
+    let result = common::analyze_result(
+        "test_analysis_result_niche_reference_parameter",
+        "test_analysis_result_niche_reference_parameter",
+        "
+            fn test_analysis_result_niche_reference_parameter(x: Option<&i32>) -> i32 {
+                *x.unwrap()
+            }
+        ",
+        &AnalysisConfig::new().replay(false),
+    );
+
+    match result.unwrap() {
+        AnalysisResult::Unsafe { counterexample: Some(counterexample) } => {
+            let argument = counterexample.arguments.iter().find(|argument| argument.name == "x").unwrap();
+            assert_eq!(argument.value, "None");
+        }
+        _ => panic!("Expected an unsafe result"),
+    }
+}
+
+#[test]
+fn test_min_max_int_of_any_width() {
+    assert_eq!(get_min_max_signed_int(8).ok(), Some((i8::MIN as i128, i8::MAX as i128)));
+    assert_eq!(get_min_max_signed_int(24).ok(), Some((-(1 << 23), (1 << 23) - 1)));
+    assert_eq!(get_min_max_signed_int(128).ok(), Some((i128::MIN, i128::MAX)));
+    assert_eq!(get_min_max_unsigned_int(1).ok(), Some((0, 1)));
+    assert_eq!(get_min_max_unsigned_int(24).ok(), Some((0, (1 << 24) - 1)));
+    assert_eq!(get_min_max_unsigned_int(128).ok(), Some((0, u128::MAX)));
+    assert!(matches!(get_min_max_signed_int(0), Err(WombatError::UnsupportedIntegerType(_))));
+    assert!(matches!(get_min_max_unsigned_int(129), Err(WombatError::UnsupportedIntegerType(_))));
+}
+
+#[test]
+fn test_input_kind_from_file_name() {
+    assert_eq!(InputKind::from_file_name("tests_temp/test.rs"), InputKind::Source);
+    assert_eq!(InputKind::from_file_name("target/debug/deps/my_crate-0123abcd.bc"), InputKind::Bytecode);
+    assert_eq!(InputKind::from_file_name("reproducer.ll"), InputKind::Bytecode);
+}